@@ -1,9 +1,65 @@
+use std::{env, path::Path, process::ExitCode};
+
+use rclishell::script::Script;
+
 mod rcliparser;
 mod rcliterminal;
 mod rclilogger;
 mod rclishell;
 
-fn main() {
+const USAGE: &str = "Usage: rcli [script.rcli | -c \"commands\"] [-e | --stop-on-error] [--norc]";
+
+fn main() -> ExitCode {
+    let mut script_path: Option<String> = None;
+    let mut command: Option<String> = None;
+    let mut stop_on_error = false;
+    let mut load_rc = true;
+
+    let mut arguments = env::args().skip(1);
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-c" | "--command" => {
+                match arguments.next() {
+                    Some(commands) => command = Some(commands),
+                    None => {
+                        eprintln!("{}", USAGE);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            },
+            "-e" | "--stop-on-error" => {
+                stop_on_error = true;
+            },
+            "--norc" => {
+                load_rc = false;
+            },
+            _ if argument.starts_with('-') => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            },
+            _ => {
+                script_path = Some(argument);
+            }
+        }
+    }
+
     let shell = rclishell::shell::Shell::new();
-    shell.run();
+
+    if let Some(commands) = command {
+        let script = Script::from_string(String::from("-c"), &commands);
+        return shell.run_script(&script, stop_on_error);
+    }
+
+    if let Some(path) = script_path {
+        match Script::from_file(Path::new(&path)) {
+            Ok(script) => return shell.run_script(&script, stop_on_error),
+            Err(error) => {
+                eprintln!("{}: {}", path, error);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    return shell.run(load_rc);
 }
@@ -0,0 +1,436 @@
+use std::fs;
+use std::io::Error;
+
+use crate::rcliterminal::terminal::Terminal;
+
+use super::input_reader;
+use super::objects::data_types::Data;
+use super::objects::statement_objects::{ChainType, Statement};
+use super::parser;
+use super::utils::functions;
+
+///Nested user function calls allowed before a call fails, so recursion can't overflow the stack.
+const MAX_FUNCTION_DEPTH: usize = 100;
+
+
+///How evaluation continues after a statement.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Flow{
+    NEXT,
+    EXIT,
+    STOP
+}
+
+///Evaluates statements on top of the parser. Command status is used as the condition
+///for if, while, && and ||.
+pub struct Evaluator<'a>{
+    terminal_instance: &'a mut Terminal,
+    stop_on_error: bool,
+    arguments: Vec<Vec<String>>,
+    last_status: u8
+}
+
+impl<'a> Evaluator<'a>{
+    ///Starts from the terminal's last status, so $? carries over between prompts.
+    pub fn new(terminal_instance: &'a mut Terminal, stop_on_error: bool) -> Self{
+        let last_status = terminal_instance.get_last_status();
+        return Evaluator { terminal_instance: terminal_instance, stop_on_error: stop_on_error, arguments: vec![], last_status: last_status }
+    }
+
+    ///Status of the last executed command. 0 means success.
+    pub fn get_status(&self) -> u8{
+        return self.last_status
+    }
+
+    ///Evaluates statements in order. Every command result is passed to report with its line number.
+    pub fn evaluate(&mut self, statements: &Vec<Statement>, report: &mut dyn FnMut(usize, &Result<Data, Error>)) -> Flow{
+        for statement in statements{
+            //skip chained statements whose condition failed
+            match statement.get_chain(){
+                ChainType::AND if self.last_status != 0 => continue,
+                ChainType::OR if self.last_status == 0 => continue,
+                _ => {}
+            }
+
+            let flow = match statement {
+                Statement::Command { line, command, .. } => {
+                    self.command(*line, command, report)
+                },
+                Statement::If { branches, otherwise, .. } => {
+                    self.r#if(branches, otherwise, report)
+                },
+                Statement::For { line, variable, items, body, .. } => {
+                    self.r#for(*line, variable, items, body, report)
+                },
+                Statement::While { condition, body, .. } => {
+                    self.r#while(condition, body, report)
+                },
+                Statement::Function { name, body, .. } => {
+                    self.terminal_instance.set_function(name.clone(), body.clone());
+                    self.last_status = 0;
+                    Flow::NEXT
+                }
+            };
+
+            if flow != Flow::NEXT{
+                return flow
+            }
+        }
+        return Flow::NEXT
+    }
+
+    fn command(&mut self, line: usize, command: &str, report: &mut dyn FnMut(usize, &Result<Data, Error>)) -> Flow{
+        let expanded = self.expand_variables(command);
+        let words = split_words(&expanded);
+
+        if words.is_empty(){
+            return Flow::NEXT
+        }
+
+        //variable assignment, name=value
+        let assignment = words[0].split_once('=');
+        if words.len() == 1 && assignment.is_some_and(|(name, _)| is_variable_name(name)){
+            let (name, value) = assignment.unwrap();
+            self.terminal_instance.set_variable(name.to_string(), value.to_string());
+            self.last_status = 0;
+
+            return Flow::NEXT
+        }
+
+        //user defined function call
+        if let Some(function) = self.terminal_instance.get_function(&words[0]){
+            //fails like any other command, so the caller goes on unless it stops on errors
+            if self.arguments.len() >= MAX_FUNCTION_DEPTH{
+                self.last_status = 1;
+                report(line, &Err(Error::other(format!("Invoker Error: {} exceeded {} nested function calls.", words[0], MAX_FUNCTION_DEPTH))));
+
+                return if self.stop_on_error { Flow::STOP } else { Flow::NEXT }
+            }
+
+            self.arguments.push(words[1..].to_vec());
+            let flow = self.evaluate(&function, report);
+            self.arguments.pop();
+
+            return flow
+        }
+
+        let operation_result = parser::parse(expanded, self.terminal_instance);
+        self.last_status = exit_status(&operation_result);
+
+        report(line, &operation_result);
+
+        match operation_result{
+            Ok(Data::StatusData(status_code)) if status_code.eq(&1) => {
+                return Flow::EXIT
+            },
+            Err(_) if self.stop_on_error => {
+                return Flow::STOP
+            },
+            _ => return Flow::NEXT
+        }
+    }
+
+    fn r#if(&mut self, branches: &Vec<(Vec<Statement>, Vec<Statement>)>, otherwise: &Vec<Statement>, report: &mut dyn FnMut(usize, &Result<Data, Error>)) -> Flow{
+        for (condition, body) in branches{
+            let flow = self.condition(condition, report);
+
+            if flow != Flow::NEXT{
+                return flow
+            }
+
+            if self.last_status == 0{
+                return self.evaluate(body, report)
+            }
+        }
+
+        self.last_status = 0;
+        return self.evaluate(otherwise, report)
+    }
+
+    ///Evaluates an if or while condition. A failing condition is not an error, so it doesn't stop
+    ///the script and its errors aren't reported, only its status is kept.
+    fn condition(&mut self, condition: &Vec<Statement>, report: &mut dyn FnMut(usize, &Result<Data, Error>)) -> Flow{
+        let stop_on_error = self.stop_on_error;
+        self.stop_on_error = false;
+
+        let flow = self.evaluate(condition, &mut |line, operation_result| {
+            if operation_result.is_ok(){
+                report(line, operation_result);
+            }
+        });
+
+        self.stop_on_error = stop_on_error;
+        return flow
+    }
+
+    fn r#for(&mut self, line: usize, variable: &str, items: &Vec<String>, body: &Vec<Statement>, report: &mut dyn FnMut(usize, &Result<Data, Error>)) -> Flow{
+        let mut values = Vec::<String>::new();
+
+        for item in items{
+            match self.expand_item(item) {
+                Ok(mut words) => values.append(&mut words),
+                Err(error) => {
+                    self.last_status = 1;
+                    report(line, &Err(error));
+
+                    return if self.stop_on_error { Flow::STOP } else { Flow::NEXT }
+                }
+            }
+        }
+
+        self.last_status = 0;
+
+        for value in values{
+            self.terminal_instance.set_variable(variable.to_string(), value);
+
+            let flow = self.evaluate(body, report);
+            if flow != Flow::NEXT{
+                return flow
+            }
+        }
+        return Flow::NEXT
+    }
+
+    fn r#while(&mut self, condition: &Vec<Statement>, body: &Vec<Statement>, report: &mut dyn FnMut(usize, &Result<Data, Error>)) -> Flow{
+        loop{
+            let flow = self.condition(condition, report);
+
+            if flow != Flow::NEXT{
+                return flow
+            }
+
+            if self.last_status != 0{
+                self.last_status = 0;
+                return Flow::NEXT
+            }
+
+            let flow = self.evaluate(body, report);
+            if flow != Flow::NEXT{
+                return flow
+            }
+        }
+    }
+
+    ///Expands a for item to values. Quoted items stay one value, unquoted ones are split
+    ///and their substitutions and wildcards are expanded.
+    fn expand_item(&mut self, item: &str) -> Result<Vec<String>, Error>{
+        let quote = item.chars().next().filter(|x| *x == '\"' || *x == '\'');
+        let expanded = self.expand_variables(item);
+        let mut values = Vec::<String>::new();
+
+        for word in split_words(&expanded){
+            match quote {
+                Some('\'') => values.push(word),
+                _ if word.contains("$(") => values.append(&mut input_reader::substitute_word(&word, quote.is_some(), self.terminal_instance)?),
+                Some(_) => values.push(word),
+                None => values.append(&mut self.expand_glob(word))
+            }
+        }
+
+        return Ok(values)
+    }
+
+    ///Expands $name, ${name}, positional arguments ($1, $2...), $# and $? outside of single quotes.
+    fn expand_variables(&self, text: &str) -> String{
+        let mut output = String::new();
+        let mut single_quotes = false;
+        let mut iterator = text.chars().peekable();
+
+        while let Some(character) = iterator.next(){
+            if character == '\''{
+                single_quotes = !single_quotes;
+                output.push(character);
+                continue;
+            }
+            if character != '$' || single_quotes{
+                output.push(character);
+                continue;
+            }
+
+            let mut name = String::new();
+
+            match iterator.peek() {
+                Some('{') => {
+                    iterator.next();
+                    for next in iterator.by_ref(){
+                        if next == '}'{
+                            break;
+                        }
+                        name.push(next);
+                    }
+                },
+                Some(next) if next.is_ascii_digit() || *next == '?' || *next == '#' => {
+                    name.push(*next);
+                    iterator.next();
+                },
+                Some(next) if next.is_alphabetic() || *next == '_' => {
+                    while let Some(next) = iterator.peek(){
+                        if !(next.is_alphanumeric() || *next == '_'){
+                            break;
+                        }
+                        name.push(*next);
+                        iterator.next();
+                    }
+                },
+                //not a variable, for example $( or a lone $
+                _ => {
+                    output.push(character);
+                    continue;
+                }
+            }
+
+            output.push_str(&self.get_value(&name));
+        }
+
+        return output
+    }
+
+    fn get_value(&self, name: &String) -> String{
+        let arguments = match self.arguments.last() {
+            Some(arguments) => arguments.clone(),
+            None => vec![]
+        };
+
+        if name.eq("?"){
+            return self.last_status.to_string()
+        }
+        if name.eq("#"){
+            return arguments.len().to_string()
+        }
+
+        match name.parse::<usize>() {
+            Ok(0) => return String::new(),
+            Ok(index) => return arguments.get(index - 1).cloned().unwrap_or_default(),
+            Err(_) => return self.terminal_instance.get_variable(name).unwrap_or_default()
+        }
+    }
+
+    ///Expands wildcards in the last path component. Returns the word itself if nothing matches.
+    fn expand_glob(&self, word: String) -> Vec<String>{
+        if !word.contains(['*', '?', '[']){
+            return vec![word]
+        }
+
+        let (directory, pattern) = match word.rsplit_once(['/', '\\']) {
+            Some((directory, pattern)) => (Some(directory.to_string()), pattern.to_string()),
+            None => (None, word.clone())
+        };
+
+        let matcher = match functions::glob_to_regex(&pattern) {
+            Ok(matcher) => matcher,
+            Err(_) => return vec![word]
+        };
+
+        let search_directory = match &directory {
+            Some(directory) => self.terminal_instance.get_current_directory().join(directory),
+            None => self.terminal_instance.get_current_directory()
+        };
+
+        let mut matches = Vec::<String>::new();
+
+        if let Ok(entries) = fs::read_dir(search_directory){
+            for entry in entries.flatten(){
+                let name = entry.file_name().to_string_lossy().to_string();
+
+                //hidden entries only match if asked for explicitly
+                if name.starts_with('.') && !pattern.starts_with('.'){
+                    continue;
+                }
+
+                if matcher.is_match(&name){
+                    match &directory {
+                        Some(directory) => matches.push(format!("{}/{}", directory, name)),
+                        None => matches.push(name)
+                    }
+                }
+            }
+        }
+
+        if matches.is_empty(){
+            return vec![word]
+        }
+        matches.sort();
+
+        return matches
+    }
+}
+
+
+///Maps an operation result to a process exit status.
+///Status data 1 (exit) and 100 (directory changed) are successful operations.
+pub fn exit_status(operation_result: &Result<Data, Error>) -> u8{
+    match operation_result{
+        Ok(Data::StatusData(status_code)) => {
+            if status_code.eq(&1) | status_code.eq(&100){
+                return 0
+            }
+            return u8::try_from(*status_code).unwrap_or(1)
+        },
+        Ok(Data::FailedData(_)) => return 1,
+        Ok(_) => return 0,
+        Err(_) => return 1
+    }
+}
+
+
+fn is_variable_name(name: &str) -> bool{
+    let mut characters = name.chars();
+
+    match characters.next() {
+        Some(first) if first.is_alphabetic() || first == '_' => {
+            return characters.all(|x| x.is_alphanumeric() || x == '_')
+        },
+        _ => return false
+    }
+}
+
+
+///Splits on whitespace outside of quotes. Quotes are removed.
+fn split_words(text: &str) -> Vec<String>{
+    let mut words = Vec::<String>::new();
+    let mut word = String::new();
+    let mut quote: Option<char> = None;
+    let mut quoted = false;
+    let mut substitution_depth: usize = 0;
+
+    for character in text.chars(){
+        //command substitution is split by the input reader
+        if substitution_depth > 0 || (character == '(' && word.ends_with('$') && quote.is_none()){
+            match character {
+                '(' => substitution_depth += 1,
+                ')' => substitution_depth -= 1,
+                _ => {}
+            }
+            word.push(character);
+            continue;
+        }
+
+        match quote{
+            Some(current) if character == current => {
+                quote = None;
+            },
+            Some(_) => {
+                word.push(character);
+            },
+            None if character == '\"' || character == '\'' => {
+                quote = Some(character);
+                quoted = true;
+            },
+            None if character.is_whitespace() => {
+                if !word.is_empty() || quoted{
+                    words.push(word.clone());
+                }
+                word.clear();
+                quoted = false;
+            },
+            None => {
+                word.push(character);
+            }
+        }
+    }
+    if !word.is_empty() || quoted{
+        words.push(word);
+    }
+
+    return words
+}
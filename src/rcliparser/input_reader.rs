@@ -1,6 +1,10 @@
-use std::io::Error;
+use std::io::{Error, ErrorKind};
+
+use crate::rcliterminal::terminal::Terminal;
 
 use super::objects::user_input::UserInput;
+use super::parser;
+use super::statement_parser;
 
 //Accepts user input and vectorizes
 pub fn accept_input(input: String) -> Result<UserInput, Error>{
@@ -13,9 +17,24 @@ pub fn accept_input(input: String) -> Result<UserInput, Error>{
     let mut quoted_word = Vec::<char>::new();
     let mut found_quotes = false;
 
+    //command substitution $( ... ) is kept as one word
+    let mut substitution_depth: usize = 0;
+
     //for char in part
     'chars: loop{
         match iterator.next(){
+            Some(character) if substitution_depth > 0 => {
+                match character {
+                    '(' => substitution_depth += 1,
+                    ')' => substitution_depth -= 1,
+                    _ => {}
+                }
+                word.push(character);
+            },
+            Some('(') if !found_quotes && word.last() == Some(&'$') => {
+                substitution_depth = 1;
+                word.push('(');
+            },
             Some('\"') | Some('\'')=> {
                 found_quotes = !found_quotes;
 
@@ -54,8 +73,46 @@ pub fn accept_input(input: String) -> Result<UserInput, Error>{
                 }
                 word.push(character);
             },
-            None => break 'chars,
+            None => {
+                //flush last word if input didn't end with a new line
+                if !word.is_empty(){
+                    let out = word.iter().collect();
+                    word.clear();
+
+                    words.push(out)
+                }
+                break 'chars
+            },
+        }
+    }
+
+    if substitution_depth > 0{
+        return Err(Error::new(ErrorKind::InvalidInput, "Input error: Missing ')' in command substitution."))
+    }
+
+    let size = words.len();
+
+    return Ok(UserInput {
+        vector_input:words.into(), 
+        vector_length:size, 
+        peek_index:0, 
+        analyzed: false});
+}
+
+
+///Replaces $( ... ) with the rendered result of the inner command.
+///Unquoted results are split on new lines to separate words.
+pub fn substitute_commands(input: UserInput, terminal_instance: &mut Terminal) -> Result<UserInput, Error>{
+    let mut words = Vec::<String>::new();
+
+    for word in input.vector_input{
+        if !word.contains("$("){
+            words.push(word);
+            continue;
         }
+
+        let quoted = word.starts_with('\"');
+        words.extend(substitute_word(&word, quoted, terminal_instance)?);
     }
 
     let size = words.len();
@@ -68,6 +125,117 @@ pub fn accept_input(input: String) -> Result<UserInput, Error>{
 }
 
 
+///Replaces every $( ... ) in a word. Quoted results are joined with spaces, unquoted ones become one word per line.
+pub fn substitute_word(word: &str, quoted: bool, terminal_instance: &mut Terminal) -> Result<Vec<String>, Error>{
+    let mut substituted = vec![String::new()];
+    let mut remaining = word;
+
+    while let Some(start) = remaining.find("$("){
+        let end = match find_closing(&remaining[start + 2..]) {
+            Some(end) => start + 2 + end,
+            None => return Err(Error::new(ErrorKind::InvalidInput, "Input error: Missing ')' in command substitution."))
+        };
+
+        substituted.last_mut().unwrap().push_str(&remaining[..start]);
+
+        let inner = remaining[start + 2..end].to_string();
+        check_substitution(&inner)?;
+        let lines = parser::parse(inner, terminal_instance)?.render();
+
+        if quoted{
+            substituted.last_mut().unwrap().push_str(&lines.join(" "));
+        }
+        else{
+            //word splitting on new lines
+            for (index, line) in lines.iter().enumerate(){
+                if index > 0{
+                    substituted.push(String::new());
+                }
+                substituted.last_mut().unwrap().push_str(line);
+            }
+        }
+
+        remaining = &remaining[end + 1..];
+    }
+    substituted.last_mut().unwrap().push_str(remaining);
+
+    return Ok(substituted.into_iter().filter(|x| !x.is_empty()).collect())
+}
+
+
+///A substitution runs a single command, statements chained with ';', '&&' or '||' aren't evaluated.
+fn check_substitution(inner: &str) -> Result<(), Error>{
+    if statement_parser::split_segments(inner).len() > 1{
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Input error: $({}) chains commands, a substitution runs a single command.", inner)))
+    }
+    return Ok(())
+}
+
+
+///Splits text on whitespace outside of quotes and command substitutions.
+///Words are kept as written, quotes included, so they can be expanded later.
+pub fn split_raw_words(text: &str) -> Vec<String>{
+    let mut words = Vec::<String>::new();
+    let mut word = String::new();
+    let mut quote: Option<char> = None;
+    let mut substitution_depth: usize = 0;
+
+    for character in text.chars(){
+        match character {
+            _ if substitution_depth > 0 => {
+                match character {
+                    '(' => substitution_depth += 1,
+                    ')' => substitution_depth -= 1,
+                    _ => {}
+                }
+            },
+            '(' if quote.is_none() && word.ends_with('$') => {
+                substitution_depth = 1;
+            },
+            '\"' | '\'' if quote.is_none() => {
+                quote = Some(character);
+            },
+            _ if quote == Some(character) => {
+                quote = None;
+            },
+            _ if quote.is_none() && character.is_whitespace() => {
+                if !word.is_empty(){
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            },
+            _ => {}
+        }
+        word.push(character);
+    }
+    if !word.is_empty(){
+        words.push(word);
+    }
+
+    return words
+}
+
+
+///Index of the parenthesis closing an already opened one.
+fn find_closing(text: &str) -> Option<usize>{
+    let mut depth: usize = 1;
+
+    for (index, character) in text.char_indices(){
+        match character {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0{
+                    return Some(index)
+                }
+            },
+            _ => {}
+        }
+    }
+    return None
+}
+
+
 #[cfg(test)]
 mod tests {
     use crate::rcliparser::objects::user_input::{Consumable, Peekable};
@@ -106,6 +274,24 @@ mod tests {
         assert_eq!(input.peek_index, 3);
     }
 
+    #[test]
+    fn test_substitution_word(){
+        let input = accept_input("cd $(find my project) -d x".to_string()).ok().unwrap();
+        assert_eq!(input.vector_input[0], "cd");
+        assert_eq!(input.vector_input[1], "$(find my project)");
+        assert_eq!(input.vector_input[2], "-d");
+        assert_eq!(input.vector_length, 4);
+
+        assert!(accept_input("echo $(cwd".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_chained_substitution(){
+        assert!(check_substitution("find a -d \"x;y\"").is_ok());
+        assert!(check_substitution("find a; cwd").is_err());
+        assert!(check_substitution("cwd && cwd").is_err());
+    }
+
     #[test]
     fn test_consume(){
         let mut input = accept_input("list --hidden".to_string()).ok().unwrap();
@@ -1,14 +1,21 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
 use std::io::{Error, ErrorKind};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::rcliparser::objects::grammar_objects::CommandType;
 use crate::rcliterminal::terminal::Terminal;
 
 use super::objects::data_types::Data;
 use super::objects::grammar_objects::FlagType;
-use super::objects::token_objects::Invocator;
+use super::objects::token_objects::{InvocationObject, Invocator};
+use super::parser;
+use super::utils::copying::{CopyOptions, Overwrite};
+use super::utils::encodings::Encoding;
 use super::utils::functions;
+use super::utils::hashes::HashAlgorithm;
+use super::utils::search::{self, FindOptions, SearchOptions};
 
 
 pub fn invoke(invocation: Invocator, terminal_instance: &mut Terminal) -> Result<Data, Error>{
@@ -22,6 +29,51 @@ pub fn invoke(invocation: Invocator, terminal_instance: &mut Terminal) -> Result
         }
         _ => unreachable!()
     };
+    //objects are optional for help and for text commands reading piped data
+    match core_command {
+        CommandType::HELP => {
+            return help(data.pop_front(), terminal_instance)
+        },
+        CommandType::HEAD | CommandType::TAIL | CommandType::WC | CommandType::SORT | CommandType::UNIQ | CommandType::CUT => {
+            return text(core_command.clone(), data, invocation.get_piped(), invocation.get_flags(), terminal_instance.is_interactive())
+        },
+        CommandType::HASH => {
+            return hash(data, invocation.get_piped(), invocation.get_flags())
+        },
+        CommandType::HEXDUMP => {
+            if data.is_empty(){
+                return hexdump(None, invocation.get_piped(), invocation.get_flags())
+            }
+            return each_object(data, |x| hexdump(Some(x), None, invocation.get_flags()))
+        },
+        CommandType::WRITE | CommandType::APPEND => {
+            let append = core_command.eq(&CommandType::APPEND);
+            return write(data, invocation.get_piped(), invocation.get_flags(), append)
+        },
+        CommandType::TRASH => {
+            return trash(data)
+        },
+        CommandType::CD => {
+            return cd(data.pop_front(), terminal_instance)
+        },
+        CommandType::PUSHD => {
+            return pushd(data.pop_front(), terminal_instance)
+        },
+        CommandType::POPD => {
+            return functions::popd(terminal_instance)
+        },
+        CommandType::DIRS => {
+            return functions::dirs(terminal_instance)
+        },
+        _ => {}
+    }
+
+    if data.is_empty(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide an object."))
+    }
+
+    //commands working on paths run on every object, the others take the first one
+    let objects = data.clone();
     let core_object = data.pop_front().unwrap();
     let flags = invocation.get_flags();
 
@@ -33,52 +85,51 @@ pub fn invoke(invocation: Invocator, terminal_instance: &mut Terminal) -> Result
             operation_status = cwd(terminal_instance);
         },
         CommandType::ECHO => {
-            operation_status = echo(core_object)
+            operation_status = echo(objects)
         }
         CommandType::TOUCH => {
-            if data.len() >= 1{
-                operation_status = touch(core_object, Some(data));
-            }
-            else{
-                operation_status = touch(core_object, None);
-            }
-            
+            let time = match (flags.get(&FlagType::REFERENCE), flags.get(&FlagType::TIME)) {
+                (Some(Some(reference)), _) => fs::metadata(reference.get_object())?.modified()?,
+                (_, Some(Some(time))) => functions::parse_time(&time.get_object())?,
+                _ => SystemTime::now()
+            };
+
+            operation_status = each_object(objects, |x| touch(x, time));
         },
         CommandType::MKDIR => {
-            let recursive = flags.get(&FlagType::RECURSIVE);
+            let recursive = flags.get(&FlagType::RECURSIVE).is_some();
 
-            if recursive.is_some(){
-                operation_status = mkdir(core_object, true);
-            }
-            else{
-                operation_status = mkdir(core_object, false);
-            }
+            operation_status = each_object(objects, |x| mkdir(x, recursive));
         },
         CommandType::REMOVE => {
-            let recursive = flags.get(&FlagType::RECURSIVE);
+            let recursive = flags.get(&FlagType::RECURSIVE).is_some();
+
+            let force = flags.get(&FlagType::FORCE).is_some();
 
-            if recursive.is_some(){
-                operation_status = remove(core_object, true);
+            if flags.get(&FlagType::DRYRUN).is_some(){
+                operation_status = each_object(objects, |x| remove_dry_run(x, recursive));
+            }
+            else if flags.get(&FlagType::TRASH).is_some(){
+                operation_status = each_object(objects, |x| trash_put(x, recursive));
             }
             else{
-                operation_status = remove(core_object, false);
+                operation_status = each_object(objects, |x| remove(x, recursive, force));
             }
         },
         CommandType::COPY => {
             let destination = flags.get(&FlagType::DESTINATION);
-            let force: bool = (|| {
-                let flag = flags.get(&FlagType::FORCE);
+            let options = copy_options(flags, false);
 
-                if flag.is_some(){ 
-                    return true
-                }
-                return false
-            })();
+            if let Some(destination) = destination{
+                let destination_path = destination.as_ref().unwrap().get_object();
 
-            if destination.is_some(){
-                let destination_path = Data::SimpleData(destination.unwrap().as_ref().unwrap().get_object());
-                
-                operation_status = copy(core_object, destination_path, force, terminal_instance);
+                //several origins are copied into the destination directory
+                if objects.len() > 1 && !Path::new(&destination_path).is_dir(){
+                    operation_status = Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Copying several paths needs an existing directory, {} isn't one.", destination_path)));
+                }
+                else{
+                    operation_status = each_object(objects, |x| copy(x, Data::SimpleData(destination_path.clone()), &options));
+                }
             }
             else{
                 operation_status = Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide destination."));
@@ -86,26 +137,25 @@ pub fn invoke(invocation: Invocator, terminal_instance: &mut Terminal) -> Result
         },
         CommandType::MOVE => {
             let destination = flags.get(&FlagType::DESTINATION);
-            let force: bool = (|| {
-                let flag = flags.get(&FlagType::FORCE);
+            let options = copy_options(flags, true);
 
-                if flag.is_some(){ 
-                    return true
-                }
-                return false
-            })();
-
-            if destination.is_some(){
-                let destination = Data::SimpleData(destination.unwrap().as_ref().unwrap().get_object());
+            if let Some(destination) = destination{
+                let destination = Data::SimpleData(destination.as_ref().unwrap().get_object());
                 
-                operation_status = r#move(core_object, destination, force, terminal_instance);
+                operation_status = r#move(objects, destination, &options);
             }
             else{
                 operation_status = Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide destination."));
             }
         },
         CommandType::READ => {
-            operation_status = read(core_object);
+            let encoding = match flags.get(&FlagType::ENCODING) {
+                Some(Some(name)) => Some(Encoding::from_name(&name.get_object())?),
+                _ => None
+            };
+            let line_numbers = flags.get(&FlagType::LINENUMBER).is_some();
+
+            operation_status = each_object(objects, |x| read(x, encoding, line_numbers));
         },
         CommandType::LIST => {
             let hidden: bool = (|| {
@@ -125,46 +175,140 @@ pub fn invoke(invocation: Invocator, terminal_instance: &mut Terminal) -> Result
                 }
                 return false
             })();
-            operation_status = list(core_object, hidden, recursive);
+            operation_status = each_object(objects, |x| list(x, hidden, recursive));
         },
-        CommandType::CD => {
-            let mut destination_path = PathBuf::from(terminal_instance.get_current_directory());
-            destination_path.push(core_object.get_path().unwrap());
+        CommandType::GREP => {
+            //files are given with -d or as further objects
+            let mut files = data;
+            if let Some(destination) = flags.get(&FlagType::DESTINATION){
+                files.push_front(Data::SimpleData(destination.as_ref().unwrap().get_object()));
+            }
 
-            let destination_data = Data::SimpleData(destination_path.display().to_string());
-            
-            operation_status = traverse_directory(destination_data, terminal_instance);
+            operation_status = grep(core_object, files, invocation.get_piped(), flags);
         },
-        CommandType::GREP => {
-            let destination = (|| {
+        CommandType::FIND => {
+            let destination: Data = (|| {
                 let flag = flags.get(&FlagType::DESTINATION);
 
-                if flag.is_some(){
-                    let data = Data::PathData(PathBuf::from(flag.unwrap().as_ref().unwrap().get_object()));
-                    return Some(data)
+                if let Some(flag) = flag{ 
+                    return Data::SimpleData(flag.as_ref().unwrap().get_object());
                 }
-                return None
+                return Data::SimpleData(terminal_instance.get_current_directory().display().to_string())
             })();
 
-            if destination.is_some(){
-                operation_status = grep_from_path(core_object, destination.unwrap());
+            operation_status = find(core_object, destination, flags, terminal_instance)
+        }
+        CommandType::LINK => {
+            let destination = flags.get(&FlagType::DESTINATION);
+            let symbolic = flags.get(&FlagType::SYMBOLIC).is_some();
+
+            if let Some(destination) = destination{
+                let link_path = destination.as_ref().unwrap().get_object();
+
+                //several targets are linked into the destination directory under their own names
+                if objects.len() > 1 && !Path::new(&link_path).is_dir(){
+                    operation_status = Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Linking several paths needs an existing directory, {} isn't one.", link_path)));
+                }
+                else if objects.len() > 1{
+                    operation_status = each_object(objects, |x| {
+                        let name = x.get_path().and_then(|x| x.file_name()).map(|x| Path::new(&link_path).join(x).display().to_string());
+                        match name {
+                            Some(name) => link(x, Data::SimpleData(name), symbolic),
+                            None => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+                        }
+                    });
+                }
+                else{
+                    operation_status = link(core_object, Data::SimpleData(link_path), symbolic);
+                }
             }
             else{
-                operation_status = grep_from_string(core_object, data);
+                operation_status = Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide link path."));
             }
         },
-        CommandType::FIND => {
-            let destination: Data = (|| {
-                let flag = flags.get(&FlagType::DESTINATION);
+        CommandType::READLINK => {
+            operation_status = each_object(objects, readlink);
+        },
+        CommandType::REALPATH => {
+            operation_status = each_object(objects, realpath);
+        },
+        CommandType::PS => {
+            operation_status = ps();
+        },
+        CommandType::KILL => {
+            let force = flags.get(&FlagType::FORCE).is_some();
 
-                if flag.is_some(){ 
-                    return Data::SimpleData(flag.unwrap().as_ref().unwrap().get_object());
-                }
-                return Data::SimpleData(terminal_instance.get_current_directory().display().to_string())
-            })();
+            operation_status = each_object(objects, |x| kill(x, force));
+        },
+        CommandType::TEE => {
+            let append = flags.get(&FlagType::APPEND).is_some();
 
-            operation_status = find(core_object, destination)
-        }
+            operation_status = tee(objects, invocation.get_piped(), append);
+        },
+        CommandType::SED => {
+            let file = flags.get(&FlagType::DESTINATION).map(|x| Data::SimpleData(x.as_ref().unwrap().get_object()));
+            let in_place = flags.get(&FlagType::INPLACE).is_some();
+            let backup = flags.get(&FlagType::BACKUP).is_some();
+
+            operation_status = sed(core_object, file, invocation.get_piped(), in_place, backup);
+        },
+        CommandType::DU => {
+            let max_depth = match flags.contains_key(&FlagType::DEPTH) {
+                true => Some(flag_number(flags, &FlagType::DEPTH, 0)?),
+                false => None
+            };
+            let summary = flags.get(&FlagType::SUMMARY).is_some();
+            let human = flags.get(&FlagType::HUMAN).is_some();
+
+            operation_status = each_object(objects, |x| du(x, max_depth, summary, human));
+        },
+        CommandType::DF => {
+            operation_status = df(flags.get(&FlagType::HUMAN).is_some());
+        },
+        CommandType::TREE => {
+            let hidden = flags.get(&FlagType::HIDDEN).is_some();
+            let dirs_first = flags.get(&FlagType::DIRSFIRST).is_some();
+            let ascii = flags.get(&FlagType::ASCII).is_some();
+            let max_depth = match flags.contains_key(&FlagType::DEPTH) {
+                true => Some(flag_number(flags, &FlagType::DEPTH, 0)?),
+                false => None
+            };
+
+            operation_status = each_object(objects, |x| tree(x, hidden, dirs_first, max_depth, ascii));
+        },
+        CommandType::DIFF => {
+            let context = flag_number(flags, &FlagType::CONTEXT, 3)?;
+            let ignore_whitespace = flags.get(&FlagType::IGNOREWHITESPACE).is_some();
+
+            operation_status = match flags.get(&FlagType::DESTINATION) {
+                Some(destination) => diff(core_object, Data::SimpleData(destination.as_ref().unwrap().get_object()), context, ignore_whitespace),
+                None => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path to compare with."))
+            };
+        },
+        CommandType::CMP => {
+            operation_status = match flags.get(&FlagType::DESTINATION) {
+                Some(destination) => cmp(core_object, Data::SimpleData(destination.as_ref().unwrap().get_object())),
+                None => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path to compare with."))
+            };
+        },
+        CommandType::ARCHIVE => {
+            let exclude = flag_list(flags, &FlagType::EXCLUDE);
+
+            operation_status = match flags.get(&FlagType::DESTINATION) {
+                Some(destination) => archive(objects, Data::SimpleData(destination.as_ref().unwrap().get_object()), &exclude),
+                None => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide an archive to create."))
+            };
+        },
+        CommandType::EXTRACT => {
+            let destination = match flags.get(&FlagType::DESTINATION) {
+                Some(destination) => Data::SimpleData(destination.as_ref().unwrap().get_object()),
+                None => Data::PathData(terminal_instance.get_current_directory())
+            };
+            let list_only = flags.get(&FlagType::LISTONLY).is_some();
+
+            operation_status = each_object(objects, |x| extract(x, destination.clone(), list_only));
+        },
+        CommandType::HELP | CommandType::HEAD | CommandType::TAIL | CommandType::WC | CommandType::SORT | CommandType::UNIQ | CommandType::CUT | CommandType::HASH | CommandType::HEXDUMP | CommandType::WRITE | CommandType::APPEND | CommandType::TRASH | CommandType::CD | CommandType::PUSHD | CommandType::POPD | CommandType::DIRS => unreachable!(),
         CommandType::EXIT => {
             operation_status = exit();
         },
@@ -194,35 +338,19 @@ fn cwd(terminal_instance: &mut Terminal) -> Result<Data, Error>{
 }
 
 
-fn echo(string: Data) -> Result<Data, Error>{
-    match string{
-        Data::SimpleData(simple) => {
-            return functions::echo(&simple)
-        }
-        _ => unreachable!()
-    }
+///Echoes every object separated by a space.
+fn echo(strings: VecDeque<Data>) -> Result<Data, Error>{
+    let words: Vec<String> = strings.iter().flat_map(|x| x.render()).collect();
+
+    return functions::echo(&words.join(" "))
 }
 
 
-fn touch(current_path: Data, data: Option<VecDeque<Data>>) -> Result<Data, Error>{
+fn touch(current_path: Data, time: SystemTime) -> Result<Data, Error>{
     let file_path = current_path.get_path();
 
-    if file_path.is_some(){
-        if data.is_some(){
-            
-            let mut touch_buffer: Vec<String> = Vec::new();
-
-            for data_type in data.unwrap(){
-                match data_type{
-                    Data::SimpleData(simple) => {
-                        touch_buffer.push(simple) 
-                    }
-                    _ => continue
-                }
-            }
-            return functions::touch(file_path.unwrap(), Some(touch_buffer))
-        }
-        return Ok(functions::touch(file_path.unwrap(), None).unwrap())
+    if let Some(file_path) = file_path{
+        return functions::touch(file_path, time)
     }
     return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: First parameter wasn't a path."))
 }
@@ -238,9 +366,13 @@ fn mkdir(data: Data, recursive: bool) -> Result<Data, Error>{
 }
 
 
-fn remove(data: Data, recursive: bool) -> Result<Data, Error>{
+fn remove(data: Data, recursive: bool, force: bool) -> Result<Data, Error>{
     match data {
         Data::SimpleData(path) => {
+            //large trees are confirmed first unless forced
+            if recursive && !force && !functions::confirm_remove(Path::new(&path))?{
+                return Err(Error::new(ErrorKind::Interrupted, "Invoker Error: Remove cancelled."))
+            }
             return functions::remove(Path::new(&path), recursive)
         }
         _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path.")),
@@ -248,30 +380,83 @@ fn remove(data: Data, recursive: bool) -> Result<Data, Error>{
 }
 
 
-fn copy(origin_data: Data, destination_data: Data, force: bool, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+fn remove_dry_run(data: Data, recursive: bool) -> Result<Data, Error>{
+    match data {
+        Data::SimpleData(path) => {
+            return functions::remove_dry_run(Path::new(&path), recursive)
+        }
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path.")),
+    }
+}
+
+
+fn trash_put(data: Data, recursive: bool) -> Result<Data, Error>{
+    match data {
+        Data::SimpleData(path) => {
+            return functions::trash(Path::new(&path), recursive)
+        }
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path.")),
+    }
+}
+
+
+fn copy(origin_data: Data, destination_data: Data, options: &CopyOptions) -> Result<Data, Error>{
     match (origin_data, destination_data) {
         (Data::SimpleData(origin), Data::SimpleData(destination)) => {
-            return functions::copy(Path::new(&origin), Path::new(&destination), force, terminal_instance)
+            return functions::copy(Path::new(&origin), Path::new(&destination), options)
         },
         _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
     }
 }
 
 
-fn r#move(origin_data: Data, destination_data: Data, force: bool, terminal_instance: &mut Terminal) -> Result<Data, Error>{
-    match (origin_data, destination_data) {
-        (Data::SimpleData(origin), Data::SimpleData(destination)) => {
-            return functions::r#move(Path::new(&origin), Path::new(&destination), force, terminal_instance)
+fn r#move(origins: VecDeque<Data>, destination_data: Data, options: &CopyOptions) -> Result<Data, Error>{
+    let mut origin_paths = Vec::<PathBuf>::new();
+
+    for origin in origins{
+        match origin {
+            Data::SimpleData(origin) => origin_paths.push(PathBuf::from(origin)),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+        }
+    }
+
+    match destination_data {
+        Data::SimpleData(destination) => {
+            return functions::r#move(&origin_paths, Path::new(&destination), options)
         },
         _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
     }
 }
 
 
-fn read(data: Data) -> Result<Data, Error>{
+///Copy options from flags. The safest overwrite mode wins if several are given. Moves always keep metadata and links.
+fn copy_options(flags: &HashMap<FlagType, Option<InvocationObject>>, is_move: bool) -> CopyOptions{
+    let overwrite = if flags.contains_key(&FlagType::NOCLOBBER) {
+        Overwrite::NOCLOBBER
+    }
+    else if flags.contains_key(&FlagType::INTERACTIVE) {
+        Overwrite::INTERACTIVE
+    }
+    else if flags.contains_key(&FlagType::FORCE) {
+        Overwrite::FORCE
+    }
+    else {
+        Overwrite::DENY
+    };
+
+    return CopyOptions {
+        overwrite,
+        preserve: is_move || flags.contains_key(&FlagType::PRESERVE),
+        dereference: !is_move && flags.contains_key(&FlagType::DEREFERENCE),
+        progress: flags.contains_key(&FlagType::PROGRESS)
+    }
+}
+
+
+fn read(data: Data, encoding: Option<Encoding>, line_numbers: bool) -> Result<Data, Error>{
     match data {
         Data::SimpleData(path) => {
-            return functions::read(Path::new(&path));
+            return functions::read(Path::new(&path), encoding, line_numbers);
         }
         _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path.")),
     }
@@ -290,6 +475,39 @@ fn list(data: Data, hidden: bool, recursive: bool) -> Result<Data, Error>{
 
 
 
+///Changes directory. Without a directory goes home, - goes back to the previous one.
+fn cd(data: Option<Data>, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    match data {
+        None => {
+            let home = terminal_instance.get_home_directory();
+            return traverse_directory(Data::SimpleData(home.display().to_string()), terminal_instance)
+        },
+        Some(Data::SimpleData(path)) if path.eq("-") => {
+            return functions::previous_directory(terminal_instance)
+        },
+        Some(Data::SimpleData(path)) => {
+            let destination_path = terminal_instance.get_current_directory().join(path);
+            return traverse_directory(Data::SimpleData(destination_path.display().to_string()), terminal_instance)
+        },
+        Some(_) => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path.")),
+    }
+}
+
+
+fn pushd(data: Option<Data>, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    match data {
+        None => {
+            return functions::pushd(None, terminal_instance)
+        },
+        Some(Data::SimpleData(path)) => {
+            let destination_path = terminal_instance.get_current_directory().join(path);
+            return functions::pushd(Some(&destination_path), terminal_instance)
+        },
+        Some(_) => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path.")),
+    }
+}
+
+
 fn traverse_directory(data: Data, terminal_instance: &mut Terminal) -> Result<Data, Error>{
     match data {
         Data::SimpleData(path) => {
@@ -301,65 +519,540 @@ fn traverse_directory(data: Data, terminal_instance: &mut Terminal) -> Result<Da
 
 
 
-fn grep_from_path(pattern: Data, data_path: Data) -> Result<Data, Error>{
-    let string_pattern = &pattern.get_value().unwrap();
-    match data_path{
-        Data::PathData(p) => {
-            let res = functions::global_regex(&p, string_pattern);
+fn grep(pattern: Data, files: VecDeque<Data>, piped_data: Option<Data>, flags: &HashMap<FlagType, Option<InvocationObject>>) -> Result<Data, Error>{
+    let pattern = match pattern {
+        Data::SimpleData(pattern) => pattern,
+        _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a pattern."))
+    };
+
+    let context = flag_number(flags, &FlagType::CONTEXT, 0)?;
+    let options = SearchOptions {
+        ignore_case: flags.get(&FlagType::IGNORECASE).is_some(),
+        invert: flags.get(&FlagType::INVERT).is_some(),
+        fixed: flags.get(&FlagType::FIXED).is_some(),
+        word: flags.get(&FlagType::WORD).is_some(),
+        before: flag_number(flags, &FlagType::BEFORE, context)?,
+        after: flag_number(flags, &FlagType::AFTER, context)?,
+        include: flag_list(flags, &FlagType::INCLUDE),
+        exclude: flag_list(flags, &FlagType::EXCLUDE),
+        gitignore: flags.get(&FlagType::GITIGNORE).is_some()
+    };
+    let line_numbers = flags.get(&FlagType::LINENUMBER).is_some();
+    let count = flags.get(&FlagType::COUNT).is_some();
+
+    let mut paths = Vec::<PathBuf>::new();
+
+    for file in files{
+        match file {
+            Data::SimpleData(path) => paths.push(PathBuf::from(path)),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+        }
+    }
+
+    if paths.is_empty() && piped_data.is_none(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path or piped data."))
+    }
+
+    return functions::grep(&pattern, paths, piped_data.map(|x| x.render()), &options, line_numbers, count)
+}
+
+
+fn find(data: Data, target: Data, flags: &HashMap<FlagType, Option<InvocationObject>>, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    let pattern = match data {
+        Data::SimpleData(pattern) => pattern,
+        _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a name."))
+    };
+    let flag_object = |flag_type: &FlagType| flags.get(flag_type).and_then(|x| x.as_ref()).map(|x| x.get_object());
+
+    let options = FindOptions {
+        name: search::name_pattern(&pattern, flags.contains_key(&FlagType::REGEX), flags.contains_key(&FlagType::IGNORECASE))?,
+        entry_type: flag_object(&FlagType::TYPE).map(|x| search::parse_entry_type(&x)).transpose()?,
+        size: flag_object(&FlagType::SIZE).map(|x| search::parse_size(&x)).transpose()?,
+        modified: flag_object(&FlagType::MTIME).map(|x| search::parse_age(&x)).transpose()?,
+        max_depth: flag_object(&FlagType::DEPTH).map(|_| flag_number(flags, &FlagType::DEPTH, 0)).transpose()?,
+        hidden: flags.contains_key(&FlagType::HIDDEN)
+    };
+
+    let result = functions::find(target.get_path().unwrap(), &options)?;
+
+    //not finding anything is a failed status so it can be used as a condition
+    let results = match result {
+        Some(results) => results,
+        None => return Err(Error::new(ErrorKind::NotFound, "Invoker Error: No object found."))
+    };
+
+    match flag_object(&FlagType::EXEC) {
+        Some(command) => return exec(&command, results, terminal_instance),
+        None => return Ok(results)
+    }
+}
+
+
+///Runs a command for every path. {} is replaced with the path, otherwise the path is appended. Stops at the first error.
+fn exec(command: &String, paths: Data, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    let mut output_string = Vec::<String>::new();
+
+    for path in paths.render(){
+        let quoted = format!("\"{}\"", path);
+
+        let command_line = match command.contains("{}") {
+            true => command.replace("{}", &quoted),
+            false => format!("{} {}", command, quoted)
+        };
+
+        match parser::parse(command_line, terminal_instance) {
+            Ok(data) => output_string.append(&mut data.render()),
+            Err(error) => return Err(Error::new(error.kind(), format!("{}: {}", path, error)))
+        }
+    }
+
+    return Ok(Data::VecStringData(output_string))
+}
+
+
+
+fn link(target_data: Data, link_data: Data, symbolic: bool) -> Result<Data, Error>{
+    match (target_data, link_data) {
+        (Data::SimpleData(target), Data::SimpleData(link)) => {
+            return functions::link(Path::new(&target), Path::new(&link), symbolic)
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    }
+}
+
+
+fn readlink(data: Data) -> Result<Data, Error>{
+    match data {
+        Data::SimpleData(path) => {
+            return functions::readlink(Path::new(&path))
+        }
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path.")),
+    }
+}
+
+
+fn realpath(data: Data) -> Result<Data, Error>{
+    match data {
+        Data::SimpleData(path) => {
+            return functions::realpath(Path::new(&path))
+        }
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path.")),
+    }
+}
+
+
+
+fn ps() -> Result<Data, Error>{
+    return functions::ps()
+}
+
+
+fn kill(data: Data, force: bool) -> Result<Data, Error>{
+    match data {
+        Data::SimpleData(target) => {
+            return functions::kill(&target, force)
+        }
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a pid or process name.")),
+    }
+}
+
+
+
+fn tee(files: VecDeque<Data>, piped_data: Option<Data>, append: bool) -> Result<Data, Error>{
+    if piped_data.is_none(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: tee requires piped data."))
+    }
+
+    let mut file_paths: Vec<PathBuf> = vec![];
+
+    for file in files{
+        match file {
+            Data::SimpleData(path) => file_paths.push(PathBuf::from(path)),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+        }
+    }
+
+    return functions::tee(&file_paths, piped_data.unwrap(), append)
+}
+
+
+
+fn sed(expression: Data, file: Option<Data>, piped_data: Option<Data>, in_place: bool, backup: bool) -> Result<Data, Error>{
+    let expression = match expression {
+        Data::SimpleData(expression) => expression,
+        _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide an expression."))
+    };
 
-            match res{
-                Ok(ok_res) => return Ok(ok_res.unwrap()),
-                Err(err) => return Err(err),
+    match (file, piped_data) {
+        (Some(Data::SimpleData(path)), _) => {
+            if in_place{
+                return functions::sed_in_place(Path::new(&path), &expression, backup)
             }
-            
+            let lines = functions::read(Path::new(&path), None, false)?.render();
+
+            return functions::sed(lines, &expression)
         },
-        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Invalid path.")),
+        (None, Some(piped)) if !in_place => {
+            return functions::sed(piped.render(), &expression)
+        },
+        (None, Some(_)) => {
+            return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: In place editing requires a file. Use -d."))
+        },
+        _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a file or piped data."))
     }
 }
 
+fn text(command: CommandType, files: VecDeque<Data>, piped_data: Option<Data>, flags: &HashMap<FlagType, Option<InvocationObject>>, interactive: bool) -> Result<Data, Error>{
+    let count = flag_number(flags, &FlagType::LINES, 10)?;
 
-fn grep_from_string(pattern: Data, data: VecDeque<Data>) -> Result<Data, Error>{
-    let string_pattern = &pattern.get_value().unwrap();
-    let mut return_result: VecDeque<Data> = vec![].into();
+    if command.eq(&CommandType::TAIL) && flags.get(&FlagType::FOLLOW).is_some(){
+        //following only stops on Enter, a script would never finish
+        if !interactive{
+            return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Follow is only available at the prompt."))
+        }
+        match files.front() {
+            Some(Data::SimpleData(path)) if files.len() == 1 => return functions::tail_follow(Path::new(path), count),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Follow requires a single file."))
+        }
+    }
 
-    for data_type in data{
-        match data_type{
-            Data::SimpleData(simple) => {
-                let res = functions::global_regex(&simple, string_pattern)?;
+    //name and content of every file, or of the piped data
+    let mut sources = Vec::<(String, Vec<u8>)>::new();
 
-                if res.is_some(){
-                    return_result.push_front(res.unwrap());
+    for file in files{
+        match file {
+            Data::SimpleData(path) => {
+                let path = PathBuf::from(path);
+                if path.is_dir(){
+                    return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot read directory. Use ls instead."))
                 }
-                
+                if !path.exists(){
+                    return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
+                }
+                sources.push((path.display().to_string(), fs::read(&path)?));
             },
-            _ => continue
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
         }
     }
 
-    return Ok(Data::DataVector(return_result.into()))
-}
+    if sources.is_empty(){
+        match piped_data {
+            Some(piped) => {
+                let content: String = piped.render().into_iter().map(|x| x + "\n").collect();
+                sources.push((String::new(), content.into_bytes()));
+            },
+            None => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide an object."))
+        }
+    }
 
+    if command.eq(&CommandType::WC){
+        return functions::wc(sources)
+    }
 
-fn find(data: Data, target: Data) -> Result<Data, Error>{
-    match data{
-        Data::SimpleData(object) => {
-            let result = functions::find(&object, target.get_path().unwrap());
-            
-            if result.is_ok(){
-                if result.as_ref().unwrap().is_some(){
-                    return Ok(result.unwrap().unwrap())
-                }
+    let to_lines = |content: &Vec<u8>| String::from_utf8_lossy(content).lines().map(|x| x.to_string()).collect::<Vec<String>>();
+
+    //head and tail take lines from every file, each under a header with its name
+    if matches!(command, CommandType::HEAD | CommandType::TAIL) && sources.len() > 1{
+        let mut output = Vec::<String>::new();
+
+        for (index, (name, content)) in sources.iter().enumerate(){
+            if index > 0{
+                output.push(String::new());
+            }
+            output.push(format!("==> {} <==", name));
 
-                return Ok(Data::StringData("No object found".to_string()))
+            let selected = match command {
+                CommandType::HEAD => functions::head(to_lines(content), count)?,
+                _ => functions::tail(to_lines(content), count)?
+            };
+            output.extend(selected.render());
+        }
+        return Ok(Data::VecStringData(output))
+    }
+
+    let lines: Vec<String> = sources.iter().flat_map(|(_, content)| to_lines(content)).collect();
+
+    match command {
+        CommandType::HEAD => return functions::head(lines, count),
+        CommandType::TAIL => return functions::tail(lines, count),
+        CommandType::SORT => {
+            let numeric = flags.get(&FlagType::NUMERIC).is_some();
+            let reverse = flags.get(&FlagType::REVERSE).is_some();
+            let key = match flag_number(flags, &FlagType::KEY, 1)? {
+                0 => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Key fields start at 1.")),
+                field if flags.contains_key(&FlagType::KEY) => Some(field),
+                _ => None
+            };
+
+            return functions::sort(lines, numeric, reverse, key)
+        },
+        CommandType::UNIQ => return functions::uniq(lines, flags.get(&FlagType::COUNT).is_some()),
+        CommandType::CUT => {
+            let flag_object = |flag_type: &FlagType| flags.get(flag_type).and_then(|x| x.as_ref()).map(|x| x.get_object());
+
+            let delimiter = flag_object(&FlagType::DELIMITER).unwrap_or(String::from("\t"));
+            if delimiter.is_empty(){
+                return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Delimiter can't be empty."))
             }
 
-            return Err(result.unwrap_err())
+            return functions::cut(lines, &delimiter, flag_object(&FlagType::FIELDS).as_ref(), flag_object(&FlagType::CHARACTERS).as_ref())
         },
         _ => unreachable!()
     }
 }
 
 
+///Runs operation on every object. A failing object doesn't stop the others, their errors are combined into one
+///with a line per object. Affected paths are returned as a list, other outputs one after the other.
+fn each_object<F>(objects: VecDeque<Data>, mut operation: F) -> Result<Data, Error> where F: FnMut(Data) -> Result<Data, Error>{
+    //a single object keeps its own output and error
+    if objects.len() == 1{
+        return operation(objects.into_iter().next().unwrap())
+    }
+
+    let total = objects.len();
+    let mut outputs = VecDeque::<Data>::new();
+    let mut errors = Vec::<String>::new();
+
+    for object in objects{
+        let name = object.render().join(" ");
+
+        match operation(object) {
+            Ok(output) => outputs.push_back(output),
+            Err(error) => errors.push(format!("{}: {}", name, error))
+        }
+    }
+
+    if !errors.is_empty(){
+        errors.push(format!("Invoker Error: {} of {} objects failed.", errors.len(), total));
+        return Err(Error::other(errors.join("\n")))
+    }
+
+    let paths: Vec<PathBuf> = outputs.iter().filter_map(|x| match x { Data::PathData(path) => Some(path.clone()), _ => None }).collect();
+    if paths.len() == outputs.len(){
+        return Ok(Data::DirPathData(paths))
+    }
+    return Ok(Data::DataVector(Box::new(outputs)))
+}
+
+
+///Splits the object of a flag on commas. Empty if the flag isn't given.
+fn flag_list(flags: &HashMap<FlagType, Option<InvocationObject>>, flag_type: &FlagType) -> Vec<String>{
+    match flags.get(flag_type) {
+        Some(Some(object)) => return object.get_object().split(',').filter(|x| !x.is_empty()).map(|x| x.to_string()).collect(),
+        _ => return vec![]
+    }
+}
+
+
+///Parses the object of a numeric flag. Returns default if the flag isn't given.
+fn flag_number(flags: &HashMap<FlagType, Option<InvocationObject>>, flag_type: &FlagType, default: usize) -> Result<usize, Error>{
+    match flags.get(flag_type) {
+        Some(Some(object)) => {
+            let value = object.get_object();
+            return value.parse::<usize>().map_err(|_| Error::new(ErrorKind::InvalidInput, format!("Invoker Error: {} isn't a valid number.", value)))
+        },
+        _ => return Ok(default)
+    }
+}
+
+
+
+fn du(data: Data, max_depth: Option<usize>, summary: bool, human: bool) -> Result<Data, Error>{
+    match data {
+        Data::SimpleData(path) => {
+            return functions::du(Path::new(&path), max_depth, summary, human)
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    }
+}
+
+
+fn df(human: bool) -> Result<Data, Error>{
+    return functions::df(human)
+}
+
+
+
+fn tree(data: Data, hidden: bool, dirs_first: bool, max_depth: Option<usize>, ascii: bool) -> Result<Data, Error>{
+    match data {
+        Data::SimpleData(path) => {
+            let tree = functions::tree(Path::new(&path), hidden, dirs_first, max_depth)?;
+
+            //unicode rendering is the default for tree data
+            match tree {
+                Data::TreeData(node) if ascii => return Ok(Data::VecStringData(node.render(true))),
+                _ => return Ok(tree)
+            }
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    }
+}
+
+
+
+fn diff(old_data: Data, new_data: Data, context: usize, ignore_whitespace: bool) -> Result<Data, Error>{
+    match (old_data, new_data) {
+        (Data::SimpleData(old), Data::SimpleData(new)) => {
+            return functions::diff(Path::new(&old), Path::new(&new), context, ignore_whitespace)
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    }
+}
+
+
+fn cmp(first_data: Data, second_data: Data) -> Result<Data, Error>{
+    match (first_data, second_data) {
+        (Data::SimpleData(first), Data::SimpleData(second)) => {
+            return functions::cmp(Path::new(&first), Path::new(&second))
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    }
+}
+
+
+
+fn archive(source_data: VecDeque<Data>, archive_data: Data, exclude: &[String]) -> Result<Data, Error>{
+    let mut sources = Vec::<PathBuf>::new();
+
+    for source in source_data{
+        match source {
+            Data::SimpleData(source) => sources.push(PathBuf::from(source)),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+        }
+    }
+
+    match archive_data {
+        Data::SimpleData(archive) => {
+            return functions::archive(&sources, Path::new(&archive), exclude)
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    }
+}
+
+
+fn extract(archive_data: Data, destination_data: Data, list_only: bool) -> Result<Data, Error>{
+    let destination = match destination_data {
+        Data::SimpleData(destination) => PathBuf::from(destination),
+        Data::PathData(destination) => destination,
+        _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    };
+
+    match archive_data {
+        Data::SimpleData(archive) => {
+            return functions::extract(Path::new(&archive), &destination, list_only)
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    }
+}
+
+
+fn hash(files: VecDeque<Data>, piped_data: Option<Data>, flags: &HashMap<FlagType, Option<InvocationObject>>) -> Result<Data, Error>{
+    let algorithm = match flags.get(&FlagType::ALGORITHM) {
+        Some(Some(name)) => Some(HashAlgorithm::from_name(&name.get_object())?),
+        _ => None
+    };
+
+    let mut paths = Vec::<PathBuf>::new();
+    for file in files{
+        match file {
+            Data::SimpleData(path) => paths.push(PathBuf::from(path)),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+        }
+    }
+
+    if flags.get(&FlagType::CHECK).is_some(){
+        match paths.first() {
+            Some(list) if paths.len() == 1 => return functions::hash_check(list, algorithm),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Check requires a single checksum list."))
+        }
+    }
+
+    let algorithm = algorithm.unwrap_or(HashAlgorithm::SHA256);
+
+    //piped content is only hashed when no files are given
+    if paths.is_empty(){
+        match piped_data {
+            Some(piped) => {
+                let content: String = piped.render().into_iter().map(|x| x + "\n").collect();
+                return functions::hash(&paths, Some(content.into_bytes()), algorithm)
+            },
+            None => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide an object."))
+        }
+    }
+    return functions::hash(&paths, None, algorithm)
+}
+
+
+fn hexdump(file: Option<Data>, piped_data: Option<Data>, flags: &HashMap<FlagType, Option<InvocationObject>>) -> Result<Data, Error>{
+    let rows = match flags.contains_key(&FlagType::LINES) {
+        true => Some(flag_number(flags, &FlagType::LINES, 0)?),
+        false => None
+    };
+
+    match file {
+        Some(Data::SimpleData(path)) => return functions::hexdump(Some(Path::new(&path)), None, rows),
+        None => {
+            let content = piped_data.map(|x| x.render().into_iter().map(|x| x + "\n").collect::<String>().into_bytes());
+            return functions::hexdump(None, content, rows)
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a path."))
+    }
+}
+
+
+fn write(lines: VecDeque<Data>, piped_data: Option<Data>, flags: &HashMap<FlagType, Option<InvocationObject>>, append: bool) -> Result<Data, Error>{
+    let file = match flags.get(&FlagType::DESTINATION) {
+        Some(Some(file)) => PathBuf::from(file.get_object()),
+        _ => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a file to write to. Use -d."))
+    };
+
+    //literal lines are written instead of piped data
+    let lines: Vec<String> = match (lines.is_empty(), piped_data) {
+        (false, _) => lines.into_iter().flat_map(|x| x.render()).collect(),
+        (true, Some(piped)) => piped.render(),
+        (true, None) => vec![]
+    };
+
+    return functions::write(&file, lines, append)
+}
+
+
+///Trash actions: list (default), restore [name] and empty.
+fn trash(mut data: VecDeque<Data>) -> Result<Data, Error>{
+    let action = match data.pop_front() {
+        Some(Data::SimpleData(action)) => action,
+        Some(_) => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a trash action.")),
+        None => String::from("list")
+    };
+
+    match action.as_str() {
+        "list" => return functions::trash_list(),
+        "restore" => {
+            match data.pop_front() {
+                Some(Data::SimpleData(name)) => return functions::trash_restore(Some(&name)),
+                _ => return functions::trash_restore(None)
+            }
+        },
+        "empty" => return functions::trash_empty(),
+        _ => Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Unknown trash action {}. Use list, restore or empty.", action)))
+    }
+}
+
+
+fn help(command: Option<Data>, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    match command{
+        Some(Data::SimpleData(name)) => {
+            return functions::help(Some(&name), terminal_instance)
+        },
+        Some(_) => Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a command name.")),
+        None => {
+            return functions::help(None, terminal_instance)
+        }
+    }
+}
+
+
 
 fn exit() -> Result<Data, Error>{
     return functions::exit()
@@ -369,4 +1062,39 @@ fn exit() -> Result<Data, Error>{
 
 fn invalid() -> Result<Data, Error>{
     return functions::invalid()
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_flag_number(){
+        let mut flags = HashMap::<FlagType, Option<InvocationObject>>::new();
+        assert_eq!(flag_number(&flags, &FlagType::AFTER, 3).unwrap(), 3);
+
+        flags.insert(FlagType::AFTER, Some(InvocationObject::new(String::from("-3"))));
+        assert!(flag_number(&flags, &FlagType::AFTER, 0).is_err());
+    }
+
+    #[test]
+    fn test_head_files(){
+        let directory = std::env::temp_dir().join(format!("rcli-head-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("a"), "a1\na2\n").unwrap();
+        fs::write(directory.join("b"), "b1\nb2\n").unwrap();
+
+        let files = VecDeque::from([Data::SimpleData(directory.join("a").display().to_string()), Data::SimpleData(directory.join("b").display().to_string())]);
+        let mut flags = HashMap::<FlagType, Option<InvocationObject>>::new();
+        flags.insert(FlagType::LINES, Some(InvocationObject::new(String::from("1"))));
+
+        let output = text(CommandType::HEAD, files, None, &flags, false).unwrap().render();
+        assert_eq!(output, vec![
+            format!("==> {} <==", directory.join("a").display()), String::from("a1"), String::new(),
+            format!("==> {} <==", directory.join("b").display()), String::from("b1")
+        ]);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -12,6 +12,12 @@ use super::objects::user_input::{UserInput, Consumable};
 //Analyze returns a tokenqueue
 pub fn analyze(input: &mut UserInput, terminal_instance: &Terminal) -> Result<VecDeque<Token>, Error>{
     let grammar: Grammar = terminal_instance.get_instance_grammar();
+    return analyze_with(input, &grammar)
+}
+
+
+///Tokenizes input with the given grammar.
+pub fn analyze_with(input: &mut UserInput, grammar: &Grammar) -> Result<VecDeque<Token>, Error>{
     let mut tokens: Vec<Token> = Vec::new();
 
     //STEP 2: valid object. Match  ./Desktop/Files/readme.txt or ./Desktop/Files
@@ -22,6 +28,8 @@ pub fn analyze(input: &mut UserInput, terminal_instance: &Terminal) -> Result<Ve
     let mut command_string : String;
 
     let mut last_type: BnfType = BnfType::START;
+    //set after a flag that takes a value, the next word is its value even if it starts with - (find * -sz -4K)
+    let mut expects_value = false;
     loop{
         //if input analyzed break
         if input.analyzed{
@@ -39,28 +47,37 @@ pub fn analyze(input: &mut UserInput, terminal_instance: &Terminal) -> Result<Ve
             }
         };
 
+        if expects_value && grammar.get_pipe(&command_string).is_none(){
+            tokens.push(Token::TokenObject(OBJECT(command_string.clone())));
+
+            expects_value = false;
+            last_type = BnfType::OBJECT;
+            continue;
+        }
+        expects_value = false;
+
         //for available command invocations (Strings)
         let command_name = grammar.match_string_to_command(&command_string);
         
-        //STEP 1: if name matches, add command
-        if command_name.is_some(){
+        //STEP 1: if name matches and a command is expected, add command. Otherwise it is an object (help cp)
+        if command_name.is_some() && grammar.accepts_next(&last_type, &BnfType::CORE){
             tokens.push(Token::TokenCommand(COMMAND(command_string.clone())));
 
-            if !grammar.accepts_next(&last_type, &BnfType::CORE){
-                return Err(Error::new(std::io::ErrorKind::InvalidInput, "Lexer error: Incorrect format [CORE]."));
-            }
-
             last_type = BnfType::CORE;
             continue;
         }
 
         let object_found = object_matcher.captures(&command_string.as_str());
         
-        //if object found
-        if object_found.is_some(){
+        //if object found. A lone - is an object as well (cd -)
+        if object_found.is_some() || command_string.eq("-"){
             tokens.push(Token::TokenObject(OBJECT(command_string.clone())));
 
             if !grammar.accepts_next(&last_type, &BnfType::OBJECT){
+                //a command was expected
+                if grammar.accepts_next(&last_type, &BnfType::CORE){
+                    return Err(unknown_command(&command_string, grammar));
+                }
                 return Err(Error::new(std::io::ErrorKind::InvalidInput, "Lexer error: Incorrect format [OBJECT]."));
             }
             last_type = BnfType::OBJECT;
@@ -76,6 +93,7 @@ pub fn analyze(input: &mut UserInput, terminal_instance: &Terminal) -> Result<Ve
             if !grammar.accepts_next(&last_type, &BnfType::FLAG){
                 return Err(Error::new(std::io::ErrorKind::InvalidInput, "Lexer error: Incorrect format [FLAG]."));
             }
+            expects_value = grammar.get_flag(&command_string).is_some_and(|(flag_type, _)| grammar.flag_accepts_obj(flag_type));
             last_type = BnfType::FLAG;
             continue;
         }
@@ -93,4 +111,17 @@ pub fn analyze(input: &mut UserInput, terminal_instance: &Terminal) -> Result<Ve
     }
 
     return Ok(VecDeque::from(tokens));
+}
+
+
+///Unknown command error with the closest invocation as a suggestion.
+pub fn unknown_command(command: &String, grammar: &Grammar) -> Error{
+    match grammar.closest_invocation(command) {
+        Some(suggestion) => {
+            return Error::new(std::io::ErrorKind::InvalidInput, format!("Lexer error: Unknown command '{}'. Did you mean '{}'?", command, suggestion))
+        },
+        None => {
+            return Error::new(std::io::ErrorKind::InvalidInput, format!("Lexer error: Unknown command '{}'. Use help to list commands.", command))
+        }
+    }
 }
\ No newline at end of file
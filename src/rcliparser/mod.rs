@@ -1,6 +1,16 @@
 pub mod utils {
     pub mod functions;
+    pub mod archives;
+    pub mod copying;
+    pub mod disks;
+    pub mod encodings;
+    pub mod diff;
     pub mod grammar_reader;
+    pub mod hashes;
+    pub mod processes;
+    pub mod search;
+    pub mod trash;
+    #[cfg(windows)]
     pub mod windows{
         pub mod windows_file_attributes;
     }
@@ -11,9 +21,12 @@ pub mod objects{
     pub mod data_types;
     pub mod grammar_objects;
     pub mod token_objects;
+    pub mod statement_objects;
 }
 
 mod input_reader;
 mod lexical_analyzer;
 pub mod parser;
 mod invoker;
+pub mod statement_parser;
+pub mod evaluator;
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, path::{Path, PathBuf}};
+use std::{collections::VecDeque, io::{self, IsTerminal}, path::{Path, PathBuf}};
 
 #[derive(Debug, Clone)]
 pub enum Data{
@@ -15,7 +15,16 @@ pub enum Data{
     StringData(String),
     VecStringData(Vec<String>),
     DirPathData(Vec<PathBuf>),
+    ///Search results as (path, line number, line).
+    MatchData(Vec<(PathBuf, usize, String)>),
+    ///Rows of columns, shown tab separated so they can be sorted and cut.
+    TableData(Vec<Vec<String>>),
+    TreeData(TreeNode),
+    ///Diff lines. Shown coloured in a terminal.
+    DiffData(Vec<String>),
     StatusData(i32),
+    ///Output of a command that also fails, like diff when the files differ. Shown as its output, the status is used by if, && and ||.
+    FailedData(Box<Data>),
 }
 
 impl Data{
@@ -40,4 +49,156 @@ impl Data{
             _ => return None
         }
     }
+
+    ///Renders data as printable lines. Canonicalized windows paths are shown without the verbatim prefix.
+    pub fn render(&self) -> Vec<String>{
+        match &self{
+            Data::SimpleData(string) | Data::StringData(string) => {
+                return vec![string.to_string()]
+            },
+            Data::PathData(path) => {
+                return vec![display_path(path)]
+            },
+            Data::VecStringData(string_vec) | Data::DiffData(string_vec) => {
+                return string_vec.clone()
+            },
+            Data::DirPathData(path_vec) => {
+                return path_vec.iter().map(|x| display_path(x)).collect()
+            },
+            Data::MatchData(matches) => {
+                return matches.iter().map(|(path, line_number, line)| format!("{}:{}:{}", display_path(path), line_number, line)).collect()
+            },
+            Data::TableData(rows) => {
+                return rows.iter().map(|x| x.join("\t")).collect()
+            },
+            Data::TreeData(node) => {
+                return node.render(false)
+            },
+            Data::DataVector(boxed_data) => {
+                return boxed_data.iter().flat_map(|x| x.render()).collect()
+            },
+            Data::StatusData(_) => {
+                return vec![]
+            },
+            Data::FailedData(output) => {
+                return output.render()
+            }
+        }
+    }
+
+    ///Renders data for the terminal. Diffs are coloured when stdout is a terminal and plain when redirected.
+    pub fn render_terminal(&self) -> Vec<String>{
+        match &self{
+            Data::DiffData(lines) if io::stdout().is_terminal() => {
+                return lines.iter().map(color_diff_line).collect()
+            },
+            Data::FailedData(output) => {
+                return output.render_terminal()
+            },
+            _ => return self.render()
+        }
+    }
+}
+
+fn color_diff_line(line: &String) -> String{
+    let color = if line.starts_with("+++") || line.starts_with("---") {
+        "1"
+    }
+    else if line.starts_with("@@") {
+        "36"
+    }
+    else if line.starts_with('+') {
+        "32"
+    }
+    else if line.starts_with('-') {
+        "31"
+    }
+    else if line.starts_with('~') {
+        "33"
+    }
+    else {
+        return line.clone()
+    };
+
+    return format!("\x1b[{}m{}\x1b[0m", color, line)
+}
+
+///Entry of a directory tree. Children are kept in display order.
+#[derive(Debug, Clone)]
+pub struct TreeNode{
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub children: Vec<TreeNode>
+}
+
+impl TreeNode{
+    ///Renders the tree with box drawing (or ASCII) characters, followed by the number of directories and files.
+    pub fn render(&self, ascii: bool) -> Vec<String>{
+        let mut lines = vec![display_path(&self.path)];
+        let mut counts: (usize, usize) = (0, 0);
+
+        self.render_children("", ascii, &mut lines, &mut counts);
+
+        let directories = if counts.0 == 1 { "directory" } else { "directories" };
+        let files = if counts.1 == 1 { "file" } else { "files" };
+        lines.push(String::new());
+        lines.push(format!("{} {}, {} {}", counts.0, directories, counts.1, files));
+
+        return lines
+    }
+
+    fn render_children(&self, prefix: &str, ascii: bool, lines: &mut Vec<String>, counts: &mut (usize, usize)){
+        let (branch, last_branch, pipe) = match ascii {
+            true => ("|-- ", "`-- ", "|   "),
+            false => ("\u{251c}\u{2500}\u{2500} ", "\u{2514}\u{2500}\u{2500} ", "\u{2502}   ")
+        };
+
+        for (index, child) in self.children.iter().enumerate(){
+            let is_last = index == self.children.len() - 1;
+
+            lines.push(format!("{}{}{}", prefix, if is_last { last_branch } else { branch }, child.name));
+
+            if child.is_dir{
+                counts.0 += 1;
+                child.render_children(&format!("{}{}", prefix, if is_last { "    " } else { pipe }), ascii, lines, counts);
+            }
+            else{
+                counts.1 += 1;
+            }
+        }
+    }
+
+    ///Paths of all entries under the node.
+    pub fn get_paths(&self) -> Vec<PathBuf>{
+        return self.children.iter().flat_map(|x| {
+            let mut paths = vec![x.path.clone()];
+            paths.append(&mut x.get_paths());
+            paths
+        }).collect()
+    }
+}
+
+///De-canonicalizes windows paths for display.
+fn display_path(path: &Path) -> String{
+    return path.display().to_string().replace(r"\\", r"\").replace(r"\?\", r"")
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn node(name: &str, children: Vec<TreeNode>) -> TreeNode{
+        return TreeNode { name: name.to_string(), path: PathBuf::from(name), is_dir: !children.is_empty() || name.ends_with("dir"), children: children }
+    }
+
+    #[test]
+    fn test_tree_render(){
+        let tree = node("root", vec![node("a", vec![node("a/x", vec![]), node("a/y", vec![])]), node("b", vec![])]);
+
+        assert_eq!(tree.render(true), vec!["root", "|-- a", "|   |-- a/x", "|   `-- a/y", "`-- b", "", "1 directory, 3 files"]);
+        assert_eq!(tree.render(false)[3], "\u{2502}   \u{2514}\u{2500}\u{2500} a/y");
+        assert_eq!(tree.get_paths(), vec![PathBuf::from("a"), PathBuf::from("a/x"), PathBuf::from("a/y"), PathBuf::from("b")]);
+    }
 }
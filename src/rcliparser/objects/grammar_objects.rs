@@ -10,6 +10,7 @@ pub struct Grammar{
     command_invocations: HashMap<CommandType, Command>,
     pipe_commands: HashMap<PipeliningType, String>,
     flag_type: HashMap<FlagType, Flags>,
+    control_keywords: HashMap<BnfType, String>,
     bnf_grammar: HashMap<BnfType, BnfSyntax>
 }
 
@@ -34,6 +35,37 @@ impl Grammar{
         return None
     }
 
+    ///All commands with their invocations and manuals.
+    pub fn get_commands(&self) -> &HashMap<CommandType, Command>{
+        return &self.command_invocations
+    }
+
+    ///Closest command invocation by edit distance. Used to suggest a command for typos.
+    pub fn closest_invocation(&self, command: &str) -> Option<String>{
+        let mut closest: Option<(usize, String)> = None;
+
+        for (command_type, invocation_command) in &self.command_invocations{
+            if command_type.eq(&CommandType::INVALID){
+                continue;
+            }
+
+            for name in &invocation_command.invocation_name{
+                let candidate = (edit_distance(command, name), name.clone());
+
+                //ties are broken alphabetically so suggestions are stable
+                if closest.is_none() || candidate < *closest.as_ref().unwrap(){
+                    closest = Some(candidate);
+                }
+            }
+        }
+
+        match closest {
+            //suggestions that need more edits than half the word are noise
+            Some((distance, name)) if distance <= 2.max(command.chars().count() / 2) => return Some(name),
+            _ => return None
+        }
+    }
+
     pub fn match_string_to_command(&self, command: &String) -> Option<()>{
         for (_, invocation_command) in &self.command_invocations{
             let match_command =  invocation_command.match_string(command);
@@ -64,10 +96,36 @@ impl Grammar{
         return None
     }
 
+    ///Flag invocations (String) for a given type.
+    pub fn get_flag_values(&self, flag: &FlagType) -> Vec<String>{
+        match self.flag_type.get(flag){
+            Some(flags) => return flags.flag_values.clone(),
+            None => return vec![]
+        }
+    }
+
     ///If flag is followed by an object or is a sole flag. For example -destination aFile.txt
     pub fn flag_accepts_obj(&self, flag: &FlagType) -> bool{
         return self.flag_type.get(&flag).unwrap().has_obj
     }
+
+    ///If string is a control flow keyword (if, for, while...) return its type.
+    pub fn get_keyword(&self, keyword: &str) -> Option<BnfType>{
+        for (keyword_type, keyword_string) in &self.control_keywords{
+            if keyword_string.eq(keyword){
+                return Some(*keyword_type)
+            }
+        }
+        return None
+    }
+
+    ///Keyword string for a given control flow type.
+    pub fn get_keyword_string(&self, keyword_type: &BnfType) -> String{
+        match self.control_keywords.get(keyword_type){
+            Some(keyword) => return keyword.clone(),
+            None => return format!("{:?}", keyword_type).to_lowercase()
+        }
+    }
 }
 
 ///Command types used to invoke a command.
@@ -87,14 +145,63 @@ pub enum CommandType{
     GREP,
     EXIT,
     FIND,
+    LINK,
+    READLINK,
+    REALPATH,
+    PS,
+    KILL,
+    TEE,
+    HEAD,
+    TAIL,
+    WC,
+    SORT,
+    UNIQ,
+    SED,
+    CUT,
+    DU,
+    DF,
+    TREE,
+    DIFF,
+    CMP,
+    ARCHIVE,
+    EXTRACT,
+    HASH,
+    HEXDUMP,
+    WRITE,
+    APPEND,
+    TRASH,
+    PUSHD,
+    POPD,
+    DIRS,
+    HELP,
     INVALID
 }
 
-///Valid command invocations (String) and valid flag types.
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Eq, Hash)]
+impl CommandType{
+    ///Commands that read the output of a previous pipeline stage instead of taking it as their objects.
+    pub fn reads_pipe(&self) -> bool{
+        match self{
+            CommandType::GREP | CommandType::TEE | CommandType::WRITE | CommandType::APPEND => return true,
+            CommandType::HEAD | CommandType::TAIL | CommandType::WC | CommandType::SORT | CommandType::UNIQ => return true,
+            CommandType::SED | CommandType::CUT | CommandType::HASH | CommandType::HEXDUMP => return true,
+            _ => return false
+        }
+    }
+}
+
+///Valid command invocations (String), valid flag types and the manual shown by help.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Eq)]
 pub struct Command{
     invocation_name: Vec<String>,
     flags: Vec<FlagType>,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    usage: String,
+    #[serde(default)]
+    examples: Vec<String>,
+    #[serde(default)]
+    flag_descriptions: HashMap<FlagType, String>
 }
 
 impl Command{
@@ -112,6 +219,67 @@ impl Command{
     pub fn get_flags(&self) -> Vec<FlagType>{
         return self.flags.clone()
     }
+
+    pub fn get_invocation_names(&self) -> &Vec<String>{
+        return &self.invocation_name
+    }
+
+    pub fn get_summary(&self) -> &String{
+        return &self.summary
+    }
+
+    pub fn get_usage(&self) -> &String{
+        return &self.usage
+    }
+
+    pub fn get_examples(&self) -> &Vec<String>{
+        return &self.examples
+    }
+
+    pub fn get_flag_description(&self, flag: &FlagType) -> Option<&String>{
+        return self.flag_descriptions.get(flag)
+    }
+}
+
+
+///Levenshtein distance between two strings.
+fn edit_distance(first: &str, second: &str) -> usize{
+    let second_chars: Vec<char> = second.chars().collect();
+    let mut previous: Vec<usize> = (0..=second_chars.len()).collect();
+
+    for (i, first_char) in first.chars().enumerate(){
+        let mut current = vec![i + 1];
+
+        for (j, second_char) in second_chars.iter().enumerate(){
+            let substitution = previous[j] + if first_char.eq(second_char) { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    return previous[second_chars.len()]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance(){
+        assert_eq!(edit_distance("rmove", "remove"), 1);
+        assert_eq!(edit_distance("cp", "cp"), 0);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "ls"), 2);
+    }
+
+    #[test]
+    fn test_closest_invocation(){
+        let grammar = serde_json::from_str::<Grammar>(include_str!("../utils/grammar.json")).unwrap();
+        assert_eq!(grammar.closest_invocation("mkdri"), Some("mkdir".to_string()));
+        assert_eq!(grammar.closest_invocation("lst"), Some("list".to_string()));
+        assert_eq!(grammar.closest_invocation("qwertyuiop"), None);
+    }
 }
 
 
@@ -131,6 +299,54 @@ pub enum FlagType{
     DESTINATION,
     HIDDEN,
     FORCE,
+    SYMBOLIC,
+    APPEND,
+    LINES,
+    FOLLOW,
+    NUMERIC,
+    REVERSE,
+    KEY,
+    COUNT,
+    INPLACE,
+    BACKUP,
+    DELIMITER,
+    FIELDS,
+    CHARACTERS,
+    IGNORECASE,
+    INVERT,
+    FIXED,
+    WORD,
+    LINENUMBER,
+    AFTER,
+    BEFORE,
+    CONTEXT,
+    INCLUDE,
+    EXCLUDE,
+    GITIGNORE,
+    REGEX,
+    TYPE,
+    SIZE,
+    MTIME,
+    DEPTH,
+    EXEC,
+    HUMAN,
+    SUMMARY,
+    DIRSFIRST,
+    ASCII,
+    IGNOREWHITESPACE,
+    LISTONLY,
+    ALGORITHM,
+    CHECK,
+    ENCODING,
+    TIME,
+    REFERENCE,
+    INTERACTIVE,
+    NOCLOBBER,
+    PRESERVE,
+    DEREFERENCE,
+    PROGRESS,
+    TRASH,
+    DRYRUN,
 }
 
 ///All available invocations for a given flag. Can be followed by an object.
@@ -157,7 +373,23 @@ pub enum BnfType{
     FLAG,
     PIPE,
     REDIRECT,
-    END
+    END,
+    /* 
+        Control flow statements.
+    */
+    IF,
+    ELIF,
+    THEN,
+    ELSE,
+    FI,
+    FOR,
+    IN,
+    DO,
+    DONE,
+    WHILE,
+    FUNCTION,
+    BLOCKSTART,
+    BLOCKEND
 }
 
 ///Next object that can exist after current.
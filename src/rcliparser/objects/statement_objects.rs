@@ -0,0 +1,75 @@
+///How a statement is chained to the statement before it.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ChainType{
+    ALWAYS,
+    AND,
+    OR
+}
+
+///Command text separated by ';', '&&', '||' or a new line.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Segment{
+    pub line: usize,
+    pub chain: ChainType,
+    pub text: String
+}
+
+///Nested statement grammar built on top of simple commands.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Statement{
+    ///Simple command that is passed to the parser.
+    Command{
+        line: usize,
+        chain: ChainType,
+        command: String
+    },
+    ///if / elif branches as (condition, body) pairs and an optional else body.
+    If{
+        line: usize,
+        chain: ChainType,
+        branches: Vec<(Vec<Statement>, Vec<Statement>)>,
+        otherwise: Vec<Statement>
+    },
+    For{
+        line: usize,
+        chain: ChainType,
+        variable: String,
+        items: Vec<String>,
+        body: Vec<Statement>
+    },
+    While{
+        line: usize,
+        chain: ChainType,
+        condition: Vec<Statement>,
+        body: Vec<Statement>
+    },
+    ///Function definition. Arguments are available as $1, $2 etc.
+    Function{
+        line: usize,
+        name: String,
+        body: Vec<Statement>
+    }
+}
+
+impl Statement{
+    pub fn get_line(&self) -> usize{
+        match self{
+            Statement::Command { line, .. } | Statement::If { line, .. } | Statement::For { line, .. } |
+            Statement::While { line, .. } | Statement::Function { line, .. } => {
+                return *line
+            }
+        }
+    }
+
+    pub fn get_chain(&self) -> ChainType{
+        match self{
+            Statement::Command { chain, .. } | Statement::If { chain, .. } | Statement::For { chain, .. } |
+            Statement::While { chain, .. } => {
+                return *chain
+            },
+            Statement::Function { .. } => {
+                return ChainType::ALWAYS
+            }
+        }
+    }
+}
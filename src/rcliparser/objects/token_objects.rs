@@ -206,11 +206,12 @@ impl InvocationPipe{
 pub struct Invocator{
     command: CommandType,
     data: Data,
-    flags: HashMap<FlagType, Option<InvocationObject>>
+    flags: HashMap<FlagType, Option<InvocationObject>>,
+    piped: Option<Data>
 }
 impl Invocator{
-    pub fn new(command: CommandType, data: Data, flags: HashMap<FlagType, Option<InvocationObject>>) -> Self{
-        return Invocator {command: command, data: data, flags: flags}
+    pub fn new(command: CommandType, data: Data, flags: HashMap<FlagType, Option<InvocationObject>>, piped: Option<Data>) -> Self{
+        return Invocator {command: command, data: data, flags: flags, piped: piped}
     }
 
     pub fn get_type(&self) -> &CommandType{
@@ -224,4 +225,9 @@ impl Invocator{
     pub fn get_flags(&self) -> &HashMap<FlagType, Option<InvocationObject>>{
         return &self.flags
     }
+
+    ///Output of the previous pipeline stage.
+    pub fn get_piped(&self) -> Option<Data>{
+        return self.piped.clone()
+    }
 }
\ No newline at end of file
@@ -1,20 +1,27 @@
 use std::collections::{HashMap, VecDeque};
 use std::io::Error;
+use std::path::Path;
 use std::vec;
 
 use crate::rcliterminal::terminal::Terminal;
 
-use super::input_reader::accept_input;
+use super::input_reader::{accept_input, substitute_commands};
 use super::invoker;
-use super::lexical_analyzer::analyze;
+use super::lexical_analyzer::{analyze, unknown_command};
 use super::objects::data_types::Data;
-use super::objects::grammar_objects::{FlagType, PipeliningType};
+use super::objects::grammar_objects::{CommandType, FlagType, Grammar, PipeliningType};
+use super::utils::functions;
 use super::objects::token_objects::{GetValue, InvocationCommand, InvocationFlag, InvocationObject, InvocationPair, InvocationPipe, Invocator, Token};
 
 /// Function that creates a token stream
-pub fn create_stream(mut input_tokens: VecDeque<Token>, terminal_instance: &mut Terminal) -> Result<VecDeque<Token>, Error>{
+pub fn create_stream(input_tokens: VecDeque<Token>, terminal_instance: &mut Terminal) -> Result<VecDeque<Token>, Error>{
     let grammar = terminal_instance.get_instance_grammar();
+    return create_stream_with(input_tokens, &grammar, &terminal_instance.get_home_directory())
+}
+
 
+///Creates a token stream with the given grammar. Home is used for ~ in objects.
+fn create_stream_with(mut input_tokens: VecDeque<Token>, grammar: &Grammar, home: &Path) -> Result<VecDeque<Token>, Error>{
     let mut output_tokens = VecDeque::<Token>::new();
     let mut command: Option<InvocationCommand> = None;
     
@@ -27,7 +34,7 @@ pub fn create_stream(mut input_tokens: VecDeque<Token>, terminal_instance: &mut
                     let token_command = grammar.get_command(com.get_value());
 
                     if token_command.is_none(){
-                        return Err(Error::new(std::io::ErrorKind::InvalidInput, "Parser error: Invalid command."));
+                        return Err(unknown_command(com.get_value(), grammar));
                     }
                     //if a core command is found, change it with the current core_command
                     //for flag checks
@@ -36,7 +43,7 @@ pub fn create_stream(mut input_tokens: VecDeque<Token>, terminal_instance: &mut
                 }
                 //if an object is found then simply push it to stream
                 Token::TokenObject(obj) => {
-                    let invocation_obj = InvocationObject::new(obj.get_value().to_string());
+                    let invocation_obj = InvocationObject::new(object_value(obj.get_value(), home));
                     output_tokens.push_back(Token::InvocationObject(invocation_obj));
                 },
                 //if a flag is found we have to check a few things
@@ -55,13 +62,13 @@ pub fn create_stream(mut input_tokens: VecDeque<Token>, terminal_instance: &mut
                         if accepts_flag{
                             //flag accepts object so pop next as well as a pair
                             if grammar.flag_accepts_obj(flag_exists.unwrap().0){
-                                match input_tokens.pop_front().unwrap() {
-                                    Token::TokenObject(obj) => {
-                                        let invocation_obj = InvocationObject::new(obj.get_value().to_string());
+                                match input_tokens.pop_front() {
+                                    Some(Token::TokenObject(obj)) => {
+                                        let invocation_obj = InvocationObject::new(object_value(obj.get_value(), home));
                                         let pair = InvocationPair::new(flag_exists.unwrap().0.clone(), invocation_obj);
                                         output_tokens.push_back(Token::InvocationPair(pair));
                                     },
-                                    _ => unreachable!()
+                                    _ => return Err(Error::new(std::io::ErrorKind::InvalidInput, format!("Parser error: Flag {} requires a value.", flag.get_value())))
                                 };
                             }
                             //flag doesn't accept object so pop as a sole flag
@@ -105,9 +112,26 @@ pub fn create_stream(mut input_tokens: VecDeque<Token>, terminal_instance: &mut
     return Ok(output_tokens)
 }
 
+///Value of an object. Quoted objects are taken literally, otherwise a leading ~ is the home directory.
+fn object_value(value: &String, home: &Path) -> String{
+    if value.starts_with('\"'){
+        return unquote(value)
+    }
+    return functions::expand_tilde(value, home)
+}
+
+
+///Quotes are kept by the input reader so quoted words are always objects. They are removed here.
+fn unquote(value: &String) -> String{
+    if value.len() >= 2 && value.starts_with('\"') && value.ends_with('\"'){
+        return value[1..value.len() - 1].to_string()
+    }
+    return value.to_string()
+}
 
 
-pub fn call_invoker(mut input_tokens: VecDeque<Token>, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+///Invokes the command at the front of the stream. Data piped from a previous stage is passed as piped_data.
+pub fn call_invoker(mut input_tokens: VecDeque<Token>, piped_data: Option<Data>, terminal_instance: &mut Terminal) -> Result<Data, Error>{
     
     let core_command = match input_tokens.pop_front().unwrap() {
         Token::InvocationCommand(core) => core,
@@ -142,16 +166,12 @@ pub fn call_invoker(mut input_tokens: VecDeque<Token>, terminal_instance: &mut T
                 },
                 //Pipe found
                 Token::InvocationPipe(pipe) => {
-                    //if vec is empty it means no objects found so add cwd
-                    if data_vector.is_empty(){
-                        let path_data = terminal_instance.get_current_directory().display().to_string();
-                        data_vector.push(Data::SimpleData(path_data));
-                    }
+                    default_objects(&mut data_vector, &core_command.get_type(), &piped_data, &terminal_instance.get_instance_grammar(), &terminal_instance.get_current_directory())?;
                     
                     output_data = Data::DataVector(Box::new(VecDeque::from(data_vector.clone())));
                     
                     //call invoker with the first half of the command
-                    let invocation_token = Invocator::new(core_command.get_type(), output_data, flags.clone());
+                    let invocation_token = Invocator::new(core_command.get_type(), output_data, flags.clone(), piped_data.clone());
                     let invocation_result = invoker::invoke(invocation_token, terminal_instance);
 
                     if invocation_result.is_err(){
@@ -159,31 +179,9 @@ pub fn call_invoker(mut input_tokens: VecDeque<Token>, terminal_instance: &mut T
                     }
                     
                     match pipe.get_type(){
-                        //append invoker return type to end of tokens and call parser again
+                        //pass invoker output to the next command as piped data
                         PipeliningType::PIPE => {
-                            match invocation_result.unwrap(){
-                                Data::SimpleData(data) | Data::StringData(data)=> {
-                                    input_tokens.push_back(Token::InvocationObject(InvocationObject::new(data)));
-                                },
-                                Data::PathData(path) => {
-                                    input_tokens.push_back(Token::InvocationObject(InvocationObject::new(path.display().to_string())));
-                                },
-                                Data::VecStringData(string_vec) => {
-                                    for string in string_vec{
-                                        let token_object = Token::InvocationObject(InvocationObject::new(string));
-                                        input_tokens.push_back(token_object);
-                                    }
-                                },
-                                Data::DirPathData(pathbuf_vec) => {
-                                    for path in pathbuf_vec{
-                                        let token_object = Token::InvocationObject(InvocationObject::new(path.display().to_string()));
-                                        input_tokens.push_back(token_object);
-                                    }
-                                }
-                                _ => unreachable!()
-                            }
-                            
-                            return call_invoker(input_tokens, terminal_instance)
+                            return call_invoker(input_tokens, Some(invocation_result.unwrap()), terminal_instance)
                         },
                         //if redirect found then object is next
                         PipeliningType::REDIRECT => {
@@ -200,20 +198,58 @@ pub fn call_invoker(mut input_tokens: VecDeque<Token>, terminal_instance: &mut T
 
     }  
     
-    if data_vector.is_empty(){
-        let path_data = terminal_instance.get_current_directory().display().to_string();
-        data_vector.push(Data::SimpleData(path_data));
-    }
+    default_objects(&mut data_vector, &core_command.get_type(), &piped_data, &terminal_instance.get_instance_grammar(), &terminal_instance.get_current_directory())?;
 
     output_data = Data::DataVector(Box::new(VecDeque::from(data_vector)));
 
-    let invocation_token = Invocator::new(core_command.get_type(), output_data, flags);
+    let invocation_token = Invocator::new(core_command.get_type(), output_data, flags, piped_data);
     let invocation_result = invoker::invoke(invocation_token, terminal_instance);
     
     return invocation_result
 }
 
 
+///Objects from piped data and defaults. Commands that don't read piped data take it as their objects (find a | cd),
+///so it can't be combined with objects of their own. Without any objects the current directory is used.
+///Search results are taken as the files that matched and trees as their paths.
+fn default_objects(data_vector: &mut Vec<Data>, command_type: &CommandType, piped_data: &Option<Data>, grammar: &Grammar, current_directory: &Path) -> Result<(), Error>{
+    match piped_data {
+        Some(_) if command_type.reads_pipe() => {},
+        //ls | rm x would otherwise remove every listed entry too
+        Some(_) if !data_vector.is_empty() => {
+            let name = grammar.get_commands().get(command_type).and_then(|x| x.get_invocation_names().first().cloned()).unwrap_or_default();
+            return Err(Error::new(std::io::ErrorKind::InvalidInput, format!("Invoker Error: {} doesn't read piped input, give its objects either piped or directly.", name)))
+        },
+        Some(piped) => {
+            match piped {
+                Data::MatchData(matches) => {
+                    for (path, _, _) in matches{
+                        let path = Data::SimpleData(path.display().to_string());
+                        if !data_vector.iter().any(|x| x.get_value() == path.get_value()){
+                            data_vector.push(path);
+                        }
+                    }
+                },
+                Data::TreeData(node) => {
+                    node.get_paths().iter().for_each(|x| data_vector.push(Data::SimpleData(x.display().to_string())));
+                },
+                _ => piped.render().into_iter().for_each(|x| data_vector.push(Data::SimpleData(x)))
+            }
+        },
+        None if !data_vector.is_empty() => {},
+        None => {
+            //help without a command lists all commands, write without lines empties the file, trash without an action lists it,
+            //cd without a directory goes home and pushd without one swaps with the top of the stack
+            if !matches!(command_type, CommandType::HELP | CommandType::WRITE | CommandType::APPEND | CommandType::TRASH | CommandType::CD | CommandType::PUSHD | CommandType::POPD | CommandType::DIRS){
+                data_vector.push(Data::SimpleData(current_directory.display().to_string()));
+            }
+        }
+    }
+
+    return Ok(())
+}
+
+
 ///Main parser functions. Checks if all the stages until now are correct and calls call_invoker function.
 pub fn parse(user_input: String, terminal_instance: &mut Terminal) -> Result<Data, Error>{
     let user_input = accept_input(user_input);
@@ -221,6 +257,11 @@ pub fn parse(user_input: String, terminal_instance: &mut Terminal) -> Result<Dat
         return Err(user_input.err().unwrap());
     }
 
+    let user_input = substitute_commands(user_input.ok().unwrap(), terminal_instance);
+    if user_input.is_err(){
+        return Err(user_input.err().unwrap());
+    }
+
     let input_tokens = analyze(&mut user_input.ok().unwrap(), terminal_instance);
     if input_tokens.is_err(){
         return Err(input_tokens.err().unwrap());
@@ -231,5 +272,72 @@ pub fn parse(user_input: String, terminal_instance: &mut Terminal) -> Result<Dat
         return Err(parser_output.err().unwrap());
     }
     
-    return call_invoker(parser_output.unwrap(), terminal_instance)
-}
\ No newline at end of file
+    return call_invoker(parser_output.unwrap(), None, terminal_instance)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use super::super::lexical_analyzer::analyze_with;
+    use std::path::PathBuf;
+
+    fn stream(line: &str) -> Result<VecDeque<Token>, Error>{
+        let grammar = serde_json::from_str::<Grammar>(include_str!("utils/grammar.json")).unwrap();
+        let tokens = analyze_with(&mut accept_input(line.to_string())?, &grammar)?;
+        return create_stream_with(tokens, &grammar, Path::new("/home/user"))
+    }
+
+    fn flag_value(tokens: &VecDeque<Token>, flag_type: FlagType) -> Option<String>{
+        return tokens.iter().find_map(|x| match x {
+            Token::InvocationPair(pair) if pair.get_type() == flag_type => Some(pair.get_object().get_object()),
+            _ => None
+        })
+    }
+
+    #[test]
+    fn test_dash_flag_values(){
+        let tokens = stream("find * -sz -4K -mt -2h").unwrap();
+        assert_eq!(flag_value(&tokens, FlagType::SIZE), Some("-4K".to_string()));
+        assert_eq!(flag_value(&tokens, FlagType::MTIME), Some("-2h".to_string()));
+    }
+
+    #[test]
+    fn test_missing_flag_value(){
+        assert!(stream("find x -sz").is_err());
+        assert!(stream("find x -mt | cwd").is_err());
+    }
+
+    #[test]
+    fn test_context_values(){
+        let tokens = stream("grep x -A -3").unwrap();
+        assert_eq!(flag_value(&tokens, FlagType::AFTER), Some("-3".to_string()));
+        assert!(stream("grep x -A").is_err());
+        assert!(stream("grep x -C").is_err());
+        assert!(stream("grep x -B | head").is_err());
+    }
+
+    #[test]
+    fn test_piped_objects(){
+        let grammar = serde_json::from_str::<Grammar>(include_str!("utils/grammar.json")).unwrap();
+        let listed = Some(Data::DirPathData(vec![PathBuf::from("a"), PathBuf::from("b")]));
+
+        let mut objects = vec![Data::SimpleData("x".to_string())];
+        assert!(default_objects(&mut objects, &CommandType::REMOVE, &listed, &grammar, Path::new("/")).is_err());
+        assert_eq!(objects.len(), 1);
+
+        let mut objects = vec![];
+        default_objects(&mut objects, &CommandType::REMOVE, &listed, &grammar, Path::new("/")).unwrap();
+        assert_eq!(objects.iter().filter_map(|x| x.get_value().cloned()).collect::<Vec<_>>(), vec!["a", "b"]);
+
+        let mut objects = vec![Data::SimpleData("x".to_string())];
+        default_objects(&mut objects, &CommandType::HEAD, &listed, &grammar, Path::new("/")).unwrap();
+        assert_eq!(objects.len(), 1);
+    }
+
+    #[test]
+    fn test_missing_line_count(){
+        assert!(stream("tail log.txt -n").is_err());
+        assert!(stream("head -n").is_err());
+        assert_eq!(flag_value(&stream("tail log.txt -n 5 -F").unwrap(), FlagType::LINES), Some("5".to_string()));
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
+
+use super::input_reader;
+use super::objects::grammar_objects::{BnfType, Grammar};
+use super::objects::statement_objects::{ChainType, Segment, Statement};
+
+///Statement errors carry the line they occurred on.
+pub type StatementError = (usize, Error);
+
+
+///Creates statements from script source. Unterminated blocks return an UnexpectedEof error
+///so that the caller can ask for more input.
+pub fn parse_statements(source: &str, grammar: &Grammar) -> Result<Vec<Statement>, StatementError>{
+    let mut segments = VecDeque::from(split_segments(source));
+
+    let (statements, _) = parse_block(&mut segments, &[], 0, grammar)?;
+
+    return Ok(statements)
+}
+
+
+///Splits source on new lines, ';', '&&' and '||' outside of quotes.
+///Everything after an unquoted '#' that starts a word is a comment.
+pub(crate) fn split_segments(source: &str) -> Vec<Segment>{
+    let mut segments = Vec::<Segment>::new();
+
+    for (index, line) in source.lines().enumerate(){
+        let line_number = index + 1;
+
+        let mut text = String::new();
+        let mut chain = ChainType::ALWAYS;
+        let mut found_quotes = false;
+        let mut substitution_depth: usize = 0;
+
+        let mut iterator = line.chars().peekable();
+
+        'chars: loop{
+            match iterator.next(){
+                //command substitution $( ... ) is never split
+                Some(character) if substitution_depth > 0 => {
+                    match character {
+                        '(' => substitution_depth += 1,
+                        ')' => substitution_depth -= 1,
+                        _ => {}
+                    }
+                    text.push(character);
+                },
+                Some('(') if text.ends_with('$') => {
+                    substitution_depth = 1;
+                    text.push('(');
+                },
+                Some(character) if character == '\"' || character == '\'' => {
+                    found_quotes = !found_quotes;
+                    text.push(character);
+                },
+                Some(character) if found_quotes => {
+                    text.push(character);
+                },
+                //comment only if it starts a word
+                Some('#') if text.is_empty() || text.ends_with(' ') => {
+                    break 'chars;
+                },
+                Some(';') => {
+                    push_segment(&mut segments, line_number, chain, &text);
+                    text.clear();
+                    chain = ChainType::ALWAYS;
+                },
+                Some('&') if iterator.peek() == Some(&'&') => {
+                    iterator.next();
+                    push_segment(&mut segments, line_number, chain, &text);
+                    text.clear();
+                    chain = ChainType::AND;
+                },
+                Some('|') if iterator.peek() == Some(&'|') => {
+                    iterator.next();
+                    push_segment(&mut segments, line_number, chain, &text);
+                    text.clear();
+                    chain = ChainType::OR;
+                },
+                Some(character) => {
+                    text.push(character);
+                },
+                None => break 'chars,
+            }
+        }
+        push_segment(&mut segments, line_number, chain, &text);
+    }
+
+    return segments
+}
+
+
+fn push_segment(segments: &mut Vec<Segment>, line: usize, chain: ChainType, text: &str){
+    let trimmed = text.trim();
+
+    if !trimmed.is_empty(){
+        segments.push(Segment { line: line, chain: chain, text: trimmed.to_string() });
+    }
+}
+
+
+///Statements of a block and the terminator segment that closed it.
+type Block = (Vec<Statement>, Option<(BnfType, Segment)>);
+
+///Parses statements until one of the terminator keywords is found.
+///Returns the statements and the terminator segment. Text after the terminator is pushed back as a new segment.
+fn parse_block(segments: &mut VecDeque<Segment>, terminators: &[BnfType], opening_line: usize, grammar: &Grammar) -> Result<Block, StatementError>{
+    let mut statements = Vec::<Statement>::new();
+
+    loop{
+        let segment = match segments.pop_front() {
+            Some(segment) => segment,
+            None => {
+                if terminators.is_empty(){
+                    return Ok((statements, None))
+                }
+                let message = format!("Statement error: Missing '{}'.", grammar.get_keyword_string(&terminators[terminators.len() - 1]));
+                return Err((opening_line, Error::new(ErrorKind::UnexpectedEof, message)))
+            }
+        };
+
+        let (word, rest) = split_first_word(&segment.text);
+
+        match grammar.get_keyword(&word){
+            Some(keyword) if terminators.contains(&keyword) => {
+                check_next(&keyword, &rest, segment.line, grammar)?;
+                push_rest(segments, &segment, &rest);
+
+                return Ok((statements, Some((keyword, segment))))
+            },
+            Some(BnfType::IF) => {
+                check_next(&BnfType::IF, &rest, segment.line, grammar)?;
+                push_rest(segments, &segment, &rest);
+
+                statements.push(parse_if(segments, &segment, grammar)?);
+            },
+            Some(BnfType::WHILE) => {
+                check_next(&BnfType::WHILE, &rest, segment.line, grammar)?;
+                push_rest(segments, &segment, &rest);
+
+                let (condition, _) = parse_block(segments, &[BnfType::DO], segment.line, grammar)?;
+                let (body, _) = parse_block(segments, &[BnfType::DONE], segment.line, grammar)?;
+
+                statements.push(Statement::While { line: segment.line, chain: segment.chain, condition: condition, body: body });
+            },
+            Some(BnfType::FOR) => {
+                check_next(&BnfType::FOR, &rest, segment.line, grammar)?;
+                statements.push(parse_for(segments, &segment, &rest, grammar)?);
+            },
+            Some(BnfType::FUNCTION) => {
+                check_next(&BnfType::FUNCTION, &rest, segment.line, grammar)?;
+                let (name, rest) = split_first_word(&rest);
+
+                statements.push(parse_function(segments, &segment, name, &rest, grammar)?);
+            },
+            Some(keyword) => {
+                let message = format!("Statement error: Unexpected '{}'.", grammar.get_keyword_string(&keyword));
+                return Err((segment.line, Error::new(ErrorKind::InvalidInput, message)))
+            },
+            //name() { ... } function definition
+            None if word.ends_with("()") && word.len() > 2 => {
+                let name = word.trim_end_matches("()").to_string();
+
+                statements.push(parse_function(segments, &segment, name, &rest, grammar)?);
+            },
+            None => {
+                statements.push(Statement::Command { line: segment.line, chain: segment.chain, command: segment.text.clone() });
+            }
+        }
+    }
+}
+
+
+///if <condition>; then <body>; [elif <condition>; then <body>;] [else <body>;] fi
+fn parse_if(segments: &mut VecDeque<Segment>, opening: &Segment, grammar: &Grammar) -> Result<Statement, StatementError>{
+    let mut branches = Vec::<(Vec<Statement>, Vec<Statement>)>::new();
+    let mut otherwise = Vec::<Statement>::new();
+
+    'branches: loop{
+        let (condition, _) = parse_block(segments, &[BnfType::THEN], opening.line, grammar)?;
+        let (body, terminator) = parse_block(segments, &[BnfType::ELIF, BnfType::ELSE, BnfType::FI], opening.line, grammar)?;
+
+        if condition.is_empty(){
+            return Err((opening.line, Error::new(ErrorKind::InvalidInput, "Statement error: Missing condition.")))
+        }
+        branches.push((condition, body));
+
+        match terminator.unwrap().0{
+            BnfType::ELIF => {
+                continue 'branches;
+            },
+            BnfType::ELSE => {
+                (otherwise, _) = parse_block(segments, &[BnfType::FI], opening.line, grammar)?;
+                break 'branches;
+            },
+            _ => break 'branches
+        }
+    }
+
+    return Ok(Statement::If { line: opening.line, chain: opening.chain, branches: branches, otherwise: otherwise })
+}
+
+
+///for <variable> in <items>; do <body>; done
+fn parse_for(segments: &mut VecDeque<Segment>, opening: &Segment, rest: &str, grammar: &Grammar) -> Result<Statement, StatementError>{
+    let (variable, rest) = split_first_word(rest);
+    let (keyword, items) = split_first_word(&rest);
+
+    if grammar.get_keyword(&keyword) != Some(BnfType::IN){
+        let message = format!("Statement error: Expected '{}' after '{}'.", grammar.get_keyword_string(&BnfType::IN), variable);
+        return Err((opening.line, Error::new(ErrorKind::InvalidInput, message)))
+    }
+    check_next(&BnfType::IN, &items, opening.line, grammar)?;
+
+    let (leftover, _) = parse_block(segments, &[BnfType::DO], opening.line, grammar)?;
+    if !leftover.is_empty(){
+        let message = format!("Statement error: Expected '{}'.", grammar.get_keyword_string(&BnfType::DO));
+        return Err((leftover[0].get_line(), Error::new(ErrorKind::InvalidInput, message)))
+    }
+    let (body, _) = parse_block(segments, &[BnfType::DONE], opening.line, grammar)?;
+
+    //quotes and substitutions are kept, items are expanded when the loop runs
+    let items = input_reader::split_raw_words(&items);
+
+    return Ok(Statement::For { line: opening.line, chain: opening.chain, variable: variable, items: items, body: body })
+}
+
+
+///function <name> { <body> } or <name>() { <body> }
+fn parse_function(segments: &mut VecDeque<Segment>, opening: &Segment, name: String, rest: &str, grammar: &Grammar) -> Result<Statement, StatementError>{
+    //block can start on the same line or the next one
+    let (word, block_rest) = split_first_word(rest);
+
+    if grammar.get_keyword(&word) == Some(BnfType::BLOCKSTART){
+        check_next(&BnfType::BLOCKSTART, &block_rest, opening.line, grammar)?;
+        push_rest(segments, opening, &block_rest);
+    }
+    else{
+        push_rest(segments, opening, rest);
+
+        let (leftover, _) = parse_block(segments, &[BnfType::BLOCKSTART], opening.line, grammar)?;
+        if !leftover.is_empty(){
+            let message = format!("Statement error: Expected '{}'.", grammar.get_keyword_string(&BnfType::BLOCKSTART));
+            return Err((opening.line, Error::new(ErrorKind::InvalidInput, message)))
+        }
+    }
+
+    let (body, _) = parse_block(segments, &[BnfType::BLOCKEND], opening.line, grammar)?;
+
+    return Ok(Statement::Function { line: opening.line, name: name, body: body })
+}
+
+
+///Checks the BNF grammar for what can follow a keyword.
+fn check_next(keyword: &BnfType, rest: &str, line: usize, grammar: &Grammar) -> Result<(), StatementError>{
+    let (word, _) = split_first_word(rest);
+
+    let next_type = if word.is_empty(){
+        BnfType::END
+    }
+    else{
+        match grammar.get_keyword(&word) {
+            Some(next_keyword) => next_keyword,
+            None => {
+                if grammar.accepts_next(keyword, &BnfType::CORE) { BnfType::CORE } else { BnfType::OBJECT }
+            }
+        }
+    };
+
+    if !grammar.accepts_next(keyword, &next_type){
+        let message = if next_type == BnfType::END {
+            format!("Statement error: Incomplete '{}'.", grammar.get_keyword_string(keyword))
+        }
+        else{
+            format!("Statement error: Unexpected '{}' after '{}'.", word, grammar.get_keyword_string(keyword))
+        };
+        return Err((line, Error::new(ErrorKind::InvalidInput, message)))
+    }
+    return Ok(())
+}
+
+
+///Pushes text following a keyword back to the front of the segment queue.
+fn push_rest(segments: &mut VecDeque<Segment>, segment: &Segment, rest: &str){
+    if !rest.is_empty(){
+        segments.push_front(Segment { line: segment.line, chain: ChainType::ALWAYS, text: rest.to_string() });
+    }
+}
+
+
+fn split_first_word(text: &str) -> (String, String){
+    let trimmed = text.trim();
+
+    match trimmed.split_once(char::is_whitespace) {
+        Some((word, rest)) => return (word.to_string(), rest.trim().to_string()),
+        None => return (trimmed.to_string(), String::new())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::rcliparser::objects::grammar_objects::Grammar;
+
+    use super::*;
+
+    fn grammar() -> Grammar{
+        let data = include_str!("utils/grammar.json");
+        return serde_json::from_str::<Grammar>(data).unwrap()
+    }
+
+    fn command(line: usize, chain: ChainType, command: &str) -> Statement{
+        return Statement::Command { line: line, chain: chain, command: command.to_string() }
+    }
+
+    #[test]
+    fn test_segments(){
+        let segments = split_segments("# header\n\nmkdir out && cp a -d out; echo \"a;b\" || echo failed # trailing");
+        let text: Vec<(usize, ChainType, String)> = segments.into_iter().map(|x| (x.line, x.chain, x.text)).collect();
+        assert_eq!(text, vec![
+            (3, ChainType::ALWAYS, "mkdir out".to_string()),
+            (3, ChainType::AND, "cp a -d out".to_string()),
+            (3, ChainType::ALWAYS, "echo \"a;b\"".to_string()),
+            (3, ChainType::OR, "echo failed".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_substitution_segment(){
+        let segments = split_segments("cd $(find a -d $(cwd)) && echo $(cwd)");
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "cd $(find a -d $(cwd))");
+        assert_eq!(segments[1].chain, ChainType::AND);
+    }
+
+    #[test]
+    fn test_for(){
+        let statements = parse_statements("for f in *.log; do rm $f; done", &grammar()).unwrap();
+        assert_eq!(statements, vec![Statement::For {
+            line: 1,
+            chain: ChainType::ALWAYS,
+            variable: "f".to_string(),
+            items: vec!["*.log".to_string()],
+            body: vec![command(1, ChainType::ALWAYS, "rm $f")]
+        }]);
+
+        let statements = parse_statements("for f in \"my file.txt\" $(find . -n \"*.rs\") b; do cat $f; done", &grammar()).unwrap();
+        match &statements[0] {
+            Statement::For { items, .. } => assert_eq!(items, &vec!["\"my file.txt\"".to_string(), "$(find . -n \"*.rs\")".to_string(), "b".to_string()]),
+            _ => panic!("expected a for statement")
+        }
+    }
+
+    #[test]
+    fn test_if_else(){
+        let statements = parse_statements("if find config\nthen\n  cd config\nelif cwd; then echo b\nelse echo c; fi", &grammar()).unwrap();
+        assert_eq!(statements, vec![Statement::If {
+            line: 1,
+            chain: ChainType::ALWAYS,
+            branches: vec![
+                (vec![command(1, ChainType::ALWAYS, "find config")], vec![command(3, ChainType::ALWAYS, "cd config")]),
+                (vec![command(4, ChainType::ALWAYS, "cwd")], vec![command(4, ChainType::ALWAYS, "echo b")]),
+            ],
+            otherwise: vec![command(5, ChainType::ALWAYS, "echo c")]
+        }]);
+    }
+
+    #[test]
+    fn test_function(){
+        let statements = parse_statements("greet() { echo $1; }\nfunction build\n{\n  mkdir out\n}", &grammar()).unwrap();
+        assert_eq!(statements, vec![
+            Statement::Function { line: 1, name: "greet".to_string(), body: vec![command(1, ChainType::ALWAYS, "echo $1")] },
+            Statement::Function { line: 2, name: "build".to_string(), body: vec![command(4, ChainType::ALWAYS, "mkdir out")] },
+        ]);
+    }
+
+    #[test]
+    fn test_errors(){
+        let unterminated = parse_statements("while cwd; do\necho a", &grammar()).unwrap_err();
+        assert_eq!(unterminated.0, 1);
+        assert_eq!(unterminated.1.kind(), ErrorKind::UnexpectedEof);
+
+        let unexpected = parse_statements("echo a\nfi", &grammar()).unwrap_err();
+        assert_eq!(unexpected.0, 2);
+        assert_eq!(unexpected.1.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,287 @@
+use std::fs::{self, File};
+use std::io::{self, Error, ErrorKind, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::Regex;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+
+///Archive format, chosen by the file extension.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ArchiveFormat{
+    ZIP,
+    TAR,
+    TARGZ
+}
+
+impl ArchiveFormat{
+    pub fn from_path(path: &Path) -> Result<Self, Error>{
+        let name = path.file_name().map(|x| x.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+        if name.ends_with(".zip"){
+            return Ok(ArchiveFormat::ZIP)
+        }
+        if name.ends_with(".tar"){
+            return Ok(ArchiveFormat::TAR)
+        }
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz"){
+            return Ok(ArchiveFormat::TARGZ)
+        }
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Unknown archive format. Use .zip, .tar, .tar.gz or .tgz."))
+    }
+}
+
+
+///Creates an archive of files and directories. Directories are added recursively and
+///entries whose name matches an exclude pattern are skipped. Tar archives store links as links,
+///zip archives leave them out. A partial archive is removed on error.
+pub fn create(archive: &Path, sources: &[PathBuf], exclude: &[Regex]) -> Result<(), Error>{
+    let format = ArchiveFormat::from_path(archive)?;
+
+    let mut entries = Vec::<(PathBuf, String)>::new();
+    for source in sources{
+        if !source.exists(){
+            return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: Invalid path {}.", source.display())))
+        }
+        //. and .. have no name, their entries are added without a prefix
+        let name = source.file_name().map(|x| x.to_string_lossy().to_string());
+        collect_entries(source, name, exclude, &mut entries)?;
+    }
+
+    let file = File::create(archive)?;
+    //an archive written into a source must not add itself
+    let archive_path = archive.canonicalize()?;
+    entries.retain(|(path, _)| path.canonicalize().map_or(true, |x| x != archive_path));
+
+    let result = match format {
+        ArchiveFormat::ZIP => write_zip(file, &entries),
+        ArchiveFormat::TAR => write_tar(file, &entries).map(|_| ()),
+        ArchiveFormat::TARGZ => write_tar(GzEncoder::new(file, Compression::default()), &entries).and_then(|x| x.finish()).map(|_| ())
+    };
+
+    if result.is_err(){
+        let _ = fs::remove_file(archive);
+    }
+    return result
+}
+
+
+///Entry paths and names. Directory names end with /.
+fn collect_entries(path: &Path, name: Option<String>, exclude: &[Regex], entries: &mut Vec<(PathBuf, String)>) -> Result<(), Error>{
+    let metadata = fs::symlink_metadata(path)?;
+
+    if !metadata.is_dir(){
+        if let Some(name) = name{
+            entries.push((path.to_path_buf(), name));
+        }
+        return Ok(())
+    }
+
+    if let Some(name) = &name{
+        entries.push((path.to_path_buf(), format!("{}/", name)));
+    }
+
+    let mut children: Vec<fs::DirEntry> = fs::read_dir(path)?.flatten().collect();
+    children.sort_by_key(|x| x.file_name());
+
+    for child in children{
+        let child_name = child.file_name().to_string_lossy().to_string();
+        if exclude.iter().any(|x| x.is_match(&child_name)){
+            continue;
+        }
+
+        let entry_name = match &name {
+            Some(name) => format!("{}/{}", name, child_name),
+            None => child_name
+        };
+        collect_entries(&child.path(), Some(entry_name), exclude, entries)?;
+    }
+
+    return Ok(())
+}
+
+
+fn write_zip(file: File, entries: &[(PathBuf, String)]) -> Result<(), Error>{
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (path, name) in entries{
+        //links aren't followed, their targets may be outside of the sources
+        if fs::symlink_metadata(path)?.is_symlink(){
+            continue;
+        }
+
+        if name.ends_with('/'){
+            writer.add_directory(name.as_str(), options).map_err(zip_error)?;
+        }
+        else{
+            writer.start_file(name.as_str(), options).map_err(zip_error)?;
+            io::copy(&mut File::open(path)?, &mut writer)?;
+        }
+    }
+
+    writer.finish().map_err(zip_error)?;
+    return Ok(())
+}
+
+
+fn write_tar<W: Write>(writer: W, entries: &[(PathBuf, String)]) -> Result<W, Error>{
+    let mut builder = tar::Builder::new(writer);
+    //links are stored as links
+    builder.follow_symlinks(false);
+
+    for (path, name) in entries{
+        match name.strip_suffix('/') {
+            Some(directory) => builder.append_dir(directory, path)?,
+            None => builder.append_path_with_name(path, name)?
+        }
+    }
+
+    return builder.into_inner()
+}
+
+
+///Lists entry names of an archive without extracting it.
+pub fn list(archive: &Path) -> Result<Vec<String>, Error>{
+    let mut names = Vec::<String>::new();
+
+    match ArchiveFormat::from_path(archive)? {
+        ArchiveFormat::ZIP => {
+            let mut zip = ZipArchive::new(File::open(archive)?).map_err(zip_error)?;
+
+            for index in 0..zip.len(){
+                names.push(zip.by_index(index).map_err(zip_error)?.name().to_string());
+            }
+        },
+        format => {
+            let mut tar = tar::Archive::new(tar_reader(archive, format)?);
+
+            for entry in tar.entries()?{
+                names.push(entry?.path()?.display().to_string());
+            }
+        }
+    }
+
+    return Ok(names)
+}
+
+
+///Extracts an archive into destination. Every entry is checked first, so an archive with absolute paths,
+///.. components or links leaving the destination is rejected before anything is written.
+pub fn extract(archive: &Path, destination: &Path) -> Result<(), Error>{
+    let format = ArchiveFormat::from_path(archive)?;
+
+    match format {
+        ArchiveFormat::ZIP => {
+            let mut zip = ZipArchive::new(File::open(archive)?).map_err(zip_error)?;
+
+            for index in 0..zip.len(){
+                let entry = zip.by_index(index).map_err(zip_error)?;
+                if entry.enclosed_name().is_none() || !is_contained(Path::new(entry.name())){
+                    return Err(unsafe_entry(entry.name()))
+                }
+            }
+            fs::create_dir_all(destination)?;
+
+            for index in 0..zip.len(){
+                let mut entry = zip.by_index(index).map_err(zip_error)?;
+                let target = destination.join(entry.enclosed_name().unwrap());
+
+                if entry.is_dir(){
+                    fs::create_dir_all(&target)?;
+                    continue;
+                }
+                if let Some(parent) = target.parent(){
+                    fs::create_dir_all(parent)?;
+                }
+                io::copy(&mut entry, &mut File::create(&target)?)?;
+            }
+        },
+        format => {
+            let mut tar = tar::Archive::new(tar_reader(archive, format)?);
+
+            for entry in tar.entries()?{
+                let entry = entry?;
+                let path = entry.path()?.to_path_buf();
+
+                let link_leaves = match entry.link_name()? {
+                    Some(link) => !is_contained(&path.parent().unwrap_or(Path::new("")).join(link)),
+                    None => false
+                };
+                if !is_contained(&path) || link_leaves{
+                    return Err(unsafe_entry(&path.display().to_string()))
+                }
+            }
+
+            fs::create_dir_all(destination)?;
+            let mut tar = tar::Archive::new(tar_reader(archive, format)?);
+            for entry in tar.entries()?{
+                entry?.unpack_in(destination)?;
+            }
+        }
+    }
+
+    return Ok(())
+}
+
+
+///A relative path is contained when its .. components never climb above the start.
+fn is_contained(path: &Path) -> bool{
+    let mut depth: usize = 0;
+
+    for component in path.components(){
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {},
+            Component::ParentDir if depth > 0 => depth -= 1,
+            _ => return false
+        }
+    }
+    return true
+}
+
+
+fn tar_reader(archive: &Path, format: ArchiveFormat) -> Result<Box<dyn Read>, Error>{
+    let file = File::open(archive)?;
+
+    match format {
+        ArchiveFormat::TARGZ => return Ok(Box::new(GzDecoder::new(file))),
+        _ => return Ok(Box::new(file))
+    }
+}
+
+
+fn unsafe_entry(name: &str) -> Error{
+    return Error::new(ErrorKind::InvalidData, format!("Invoker Error: Archive entry {} would be extracted outside of the destination.", name))
+}
+
+
+fn zip_error(error: zip::result::ZipError) -> Error{
+    return Error::new(ErrorKind::InvalidData, format!("Invoker Error: {}", error))
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_archive_format(){
+        assert_eq!(ArchiveFormat::from_path(Path::new("out/build.ZIP")).unwrap(), ArchiveFormat::ZIP);
+        assert_eq!(ArchiveFormat::from_path(Path::new("build.tar")).unwrap(), ArchiveFormat::TAR);
+        assert_eq!(ArchiveFormat::from_path(Path::new("build.tar.gz")).unwrap(), ArchiveFormat::TARGZ);
+        assert!(ArchiveFormat::from_path(Path::new("build.rar")).is_err());
+    }
+
+    #[test]
+    fn test_is_contained(){
+        assert!(is_contained(Path::new("a/b/../c")));
+        assert!(is_contained(Path::new("./a")));
+        assert!(!is_contained(Path::new("a/../../etc/passwd")));
+        assert!(!is_contained(Path::new("/etc/passwd")));
+    }
+}
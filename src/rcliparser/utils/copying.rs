@@ -0,0 +1,267 @@
+use std::fs::{self, File, FileTimes};
+use std::io::{self, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use super::functions;
+
+#[cfg(windows)]
+use std::os::windows::fs::{self as os_fs, OpenOptionsExt};
+#[cfg(unix)]
+use std::os::unix::fs as os_fs;
+
+
+///What to do when a destination file already exists.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Overwrite{
+    DENY,
+    FORCE,
+    NOCLOBBER,
+    INTERACTIVE
+}
+
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CopyOptions{
+    pub overwrite: Overwrite,
+    ///Copy timestamps and permissions
+    pub preserve: bool,
+    ///Copy what links point to instead of the links
+    pub dereference: bool,
+    pub progress: bool
+}
+
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum EntryKind{
+    FILE,
+    DIRECTORY,
+    LINK
+}
+
+
+struct Entry{
+    source: PathBuf,
+    target: PathBuf,
+    kind: EntryKind,
+    size: u64
+}
+
+
+///Outcome of a copy. Every entry is either copied, skipped because it existed, or failed.
+#[derive(Debug, Default)]
+pub struct CopyReport{
+    pub copied: Vec<(PathBuf, PathBuf)>,
+    pub skipped: Vec<PathBuf>,
+    pub errors: Vec<(PathBuf, Error)>
+}
+
+impl CopyReport{
+    ///Error listing every failed entry, if any failed.
+    pub fn error(&self) -> Option<Error>{
+        if self.errors.is_empty(){
+            return None
+        }
+        //a single entry keeps its own error
+        if self.errors.len() == 1 && self.copied.is_empty() && self.skipped.is_empty(){
+            let (_, error) = &self.errors[0];
+            return Some(Error::new(error.kind(), error.to_string()))
+        }
+
+        let mut lines: Vec<String> = self.errors.iter().map(|(path, error)| format!("{}: {}", path.display(), error)).collect();
+        lines.push(format!("Invoker Error: {} of {} entries failed to copy.", self.errors.len(), self.errors.len() + self.copied.len() + self.skipped.len()));
+
+        return Some(Error::other(lines.join("\n")))
+    }
+
+    ///Every copied file exists at its target with the size of its source.
+    pub fn verify(&self) -> bool{
+        return self.copied.iter().all(|(source, target)| {
+            match (fs::symlink_metadata(source), fs::symlink_metadata(target)) {
+                (Ok(source), Ok(target)) => !source.is_file() || source.len() == target.len(),
+                _ => false
+            }
+        })
+    }
+}
+
+
+///Copies source to target. Directories are copied recursively and an error on one entry doesn't stop the others.
+pub fn copy_tree(source: &Path, target: &Path, options: &CopyOptions) -> CopyReport{
+    let mut report = CopyReport::default();
+    let mut entries = Vec::<Entry>::new();
+
+    plan(source, target, options.dereference, &mut entries, &mut report);
+
+    let total_bytes: u64 = entries.iter().map(|x| x.size).sum();
+    let mut copied_bytes: u64 = 0;
+
+    for (index, entry) in entries.iter().enumerate(){
+        match copy_entry(entry, options) {
+            Ok(true) => report.copied.push((entry.source.clone(), entry.target.clone())),
+            Ok(false) => report.skipped.push(entry.source.clone()),
+            Err(error) => report.errors.push((entry.source.clone(), error))
+        }
+
+        copied_bytes += entry.size;
+        if options.progress{
+            print!("\r{} {}/{} entries", progress_bar(copied_bytes, total_bytes, 30), index + 1, entries.len());
+            let _ = io::stdout().flush();
+        }
+    }
+    if options.progress && !entries.is_empty(){
+        println!();
+    }
+
+    //directory times change while their content is copied, so they are set last and deepest first
+    if options.preserve{
+        for entry in entries.iter().rev().filter(|x| x.kind == EntryKind::DIRECTORY){
+            if let Err(error) = preserve(&entry.source, &entry.target){
+                report.errors.push((entry.source.clone(), error));
+            }
+        }
+    }
+
+    return report
+}
+
+
+///Collects entries depth first, so directories come before their content.
+fn plan(source: &Path, target: &Path, dereference: bool, entries: &mut Vec<Entry>, report: &mut CopyReport){
+    let metadata = match dereference {
+        true => fs::metadata(source),
+        false => fs::symlink_metadata(source)
+    };
+    let metadata = match metadata {
+        Ok(metadata) => metadata,
+        Err(error) => return report.errors.push((source.to_path_buf(), error))
+    };
+
+    if metadata.is_symlink(){
+        return entries.push(Entry { source: source.to_path_buf(), target: target.to_path_buf(), kind: EntryKind::LINK, size: 0 })
+    }
+    if metadata.is_file(){
+        return entries.push(Entry { source: source.to_path_buf(), target: target.to_path_buf(), kind: EntryKind::FILE, size: metadata.len() })
+    }
+
+    entries.push(Entry { source: source.to_path_buf(), target: target.to_path_buf(), kind: EntryKind::DIRECTORY, size: 0 });
+
+    match fs::read_dir(source) {
+        Ok(children) => {
+            let mut children: Vec<PathBuf> = children.flatten().map(|x| x.path()).collect();
+            children.sort();
+
+            for child in children{
+                let child_target = target.join(child.file_name().unwrap());
+                plan(&child, &child_target, dereference, entries, report);
+            }
+        },
+        Err(error) => report.errors.push((source.to_path_buf(), error))
+    }
+}
+
+
+///Copies one entry. Returns false if it was skipped because the target exists.
+fn copy_entry(entry: &Entry, options: &CopyOptions) -> Result<bool, Error>{
+    if entry.kind == EntryKind::DIRECTORY{
+        if entry.target.is_file(){
+            return Err(Error::new(ErrorKind::AlreadyExists, format!("Invoker Error: {} is a file.", entry.target.display())))
+        }
+        fs::create_dir_all(&entry.target)?;
+        return Ok(true)
+    }
+
+    if fs::symlink_metadata(&entry.target).is_ok(){
+        if entry.target.is_dir() && !entry.target.is_symlink(){
+            return Err(Error::new(ErrorKind::AlreadyExists, format!("Invoker Error: {} is a directory.", entry.target.display())))
+        }
+
+        match options.overwrite {
+            Overwrite::DENY => return Err(Error::new(ErrorKind::AlreadyExists, "Invoker Error: Destination object exists, use -f to overwrite.")),
+            Overwrite::NOCLOBBER => return Ok(false),
+            Overwrite::INTERACTIVE if !functions::confirm(&format!("Overwrite {}?", entry.target.display()))? => return Ok(false),
+            _ => {}
+        }
+        //read only targets and links can't be copied over
+        fs::remove_file(&entry.target)?;
+    }
+
+    if entry.kind == EntryKind::LINK{
+        copy_link(&entry.source, &entry.target)?;
+        return Ok(true)
+    }
+
+    fs::copy(&entry.source, &entry.target)?;
+    if options.preserve{
+        preserve(&entry.source, &entry.target)?;
+    }
+    return Ok(true)
+}
+
+
+///Recreates a symbolic link with the same target.
+fn copy_link(source: &Path, target: &Path) -> Result<(), Error>{
+    let link_target = fs::read_link(source)?;
+
+    #[cfg(windows)]
+    {
+        if source.is_dir(){
+            os_fs::symlink_dir(&link_target, target)?;
+        }
+        else{
+            os_fs::symlink_file(&link_target, target)?;
+        }
+    }
+    #[cfg(unix)]
+    {
+        os_fs::symlink(&link_target, target)?;
+    }
+    return Ok(())
+}
+
+
+///Copies the access and modification times and the permissions of source to target.
+fn preserve(source: &Path, target: &Path) -> Result<(), Error>{
+    let metadata = fs::metadata(source)?;
+    let times = FileTimes::new().set_accessed(metadata.accessed()?).set_modified(metadata.modified()?);
+
+    //times only need attribute access, so read only files and directories can be opened
+    let mut open_options = File::options();
+    #[cfg(windows)]
+    {
+        //FILE_WRITE_ATTRIBUTES and FILE_FLAG_BACKUP_SEMANTICS
+        open_options.access_mode(0x100).custom_flags(0x02000000);
+    }
+    #[cfg(unix)]
+    {
+        open_options.read(true);
+    }
+    open_options.open(target)?.set_times(times)?;
+
+    fs::set_permissions(target, metadata.permissions())?;
+    return Ok(())
+}
+
+
+///Bar of the given width with the percentage done.
+pub fn progress_bar(done: u64, total: u64, width: usize) -> String{
+    let ratio = match total {
+        0 => 1.0,
+        _ => done as f64 / total as f64
+    };
+    let filled = (ratio * width as f64).round() as usize;
+
+    return format!("[{}{}] {:>3}%", "#".repeat(filled), " ".repeat(width - filled), (ratio * 100.0).round() as u64)
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_progress_bar(){
+        assert_eq!(progress_bar(0, 100, 10), "[          ]   0%");
+        assert_eq!(progress_bar(45, 100, 10), "[#####     ]  45%");
+        assert_eq!(progress_bar(0, 0, 4), "[####] 100%");
+    }
+}
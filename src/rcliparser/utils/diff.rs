@@ -0,0 +1,305 @@
+use std::fs::File;
+use std::io::{BufReader, Error, Read};
+use std::path::Path;
+
+
+///Edit of a line diff. Values are line indices in the old and new lines.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Edit{
+    EQUAL(usize, usize),
+    DELETE(usize),
+    INSERT(usize)
+}
+
+
+///Shortest edit script between two line lists. Uses the linear space variant of Myers' algorithm:
+///the lists are split where an optimal path crosses the middle, so memory grows with the input, not with the distance.
+///Ignoring whitespace compares lines with runs of whitespace collapsed and trimmed.
+pub fn diff_lines(old: &[String], new: &[String], ignore_whitespace: bool) -> Vec<Edit>{
+    let normalize = |line: &String| -> String {
+        match ignore_whitespace {
+            true => line.split_whitespace().collect::<Vec<&str>>().join(" "),
+            false => line.clone()
+        }
+    };
+    let a: Vec<String> = old.iter().map(normalize).collect();
+    let b: Vec<String> = new.iter().map(normalize).collect();
+
+    let mut edits = Vec::<Edit>::with_capacity(a.len() + b.len());
+    diff_range(&a, &b, (0, a.len()), (0, b.len()), &mut edits);
+
+    //deletions are shown before insertions in a run of changes
+    let mut start = 0;
+    while start < edits.len(){
+        let mut end = start;
+        while end < edits.len() && !matches!(edits[end], Edit::EQUAL(..)){
+            end += 1;
+        }
+        edits[start..end].sort_by_key(|x| matches!(x, Edit::INSERT(_)));
+        start = end + 1;
+    }
+
+    return edits
+}
+
+
+///Edits between a[old.0..old.1] and b[new.0..new.1]. Common ends are matched first, the rest is split and diffed recursively.
+fn diff_range(a: &[String], b: &[String], old: (usize, usize), new: (usize, usize), edits: &mut Vec<Edit>){
+    let (mut a_start, mut a_end) = old;
+    let (mut b_start, mut b_end) = new;
+
+    while a_start < a_end && b_start < b_end && a[a_start] == b[b_start]{
+        edits.push(Edit::EQUAL(a_start, b_start));
+        a_start += 1;
+        b_start += 1;
+    }
+    let mut suffix = 0;
+    while a_start < a_end && b_start < b_end && a[a_end - 1] == b[b_end - 1]{
+        a_end -= 1;
+        b_end -= 1;
+        suffix += 1;
+    }
+
+    if a_start == a_end{
+        edits.extend((b_start..b_end).map(Edit::INSERT));
+    }
+    else if b_start == b_end{
+        edits.extend((a_start..a_end).map(Edit::DELETE));
+    }
+    else{
+        match middle_split(&a[a_start..a_end], &b[b_start..b_end]) {
+            Some((x, y)) => {
+                diff_range(a, b, (a_start, a_start + x), (b_start, b_start + y), edits);
+                diff_range(a, b, (a_start + x, a_end), (b_start + y, b_end), edits);
+            },
+            //nothing in common
+            None => {
+                edits.extend((a_start..a_end).map(Edit::DELETE));
+                edits.extend((b_start..b_end).map(Edit::INSERT));
+            }
+        }
+    }
+
+    edits.extend((0..suffix).map(|x| Edit::EQUAL(a_end + x, b_end + x)));
+}
+
+
+///Point where an optimal path crosses the middle, found by searching from both ends until the paths overlap.
+///Only two diagonal vectors are kept. None if the lists have nothing in common.
+fn middle_split(a: &[String], b: &[String]) -> Option<(usize, usize)>{
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let length = 2 * max_d + 1;
+    let delta = n - m;
+    //with an odd delta the paths meet while searching forward, otherwise backward
+    let front = delta % 2 != 0;
+
+    //furthest x on every diagonal, forward from the start and backward from the end
+    let mut forward = vec![-1_isize; length as usize];
+    let mut backward = vec![-1_isize; length as usize];
+    forward[(offset + 1) as usize] = 0;
+    backward[(offset + 1) as usize] = 0;
+
+    //diagonals that ran off the grid are skipped
+    let (mut forward_start, mut forward_end, mut backward_start, mut backward_end) = (0, 0, 0, 0);
+
+    for d in 0..max_d{
+        for k in ((-d + forward_start)..=(d - forward_end)).step_by(2){
+            let index = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && forward[index - 1] < forward[index + 1]) { forward[index + 1] } else { forward[index - 1] + 1 };
+            let mut y = x - k;
+
+            while x < n && y < m && a[x as usize] == b[y as usize]{
+                x += 1;
+                y += 1;
+            }
+            forward[index] = x;
+
+            if x > n{
+                forward_end += 2;
+            }
+            else if y > m{
+                forward_start += 2;
+            }
+            else if front{
+                let backward_index = offset + delta - k;
+                if backward_index >= 0 && backward_index < length && backward[backward_index as usize] != -1 && x >= n - backward[backward_index as usize]{
+                    return Some((x as usize, y as usize))
+                }
+            }
+        }
+
+        //backward x and y count from the end
+        for k in ((-d + backward_start)..=(d - backward_end)).step_by(2){
+            let index = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && backward[index - 1] < backward[index + 1]) { backward[index + 1] } else { backward[index - 1] + 1 };
+            let mut y = x - k;
+
+            while x < n && y < m && a[(n - x - 1) as usize] == b[(m - y - 1) as usize]{
+                x += 1;
+                y += 1;
+            }
+            backward[index] = x;
+
+            if x > n{
+                backward_end += 2;
+            }
+            else if y > m{
+                backward_start += 2;
+            }
+            else if !front{
+                let forward_index = offset + delta - k;
+                if forward_index >= 0 && forward_index < length && forward[forward_index as usize] != -1{
+                    let forward_x = forward[forward_index as usize];
+                    let forward_y = forward_x - (forward_index - offset);
+                    if forward_x >= n - x{
+                        return Some((forward_x as usize, forward_y as usize))
+                    }
+                }
+            }
+        }
+    }
+
+    return None
+}
+
+
+///Unified diff hunks with the given number of context lines. Empty if there are no changes.
+pub fn unified(old: &[String], new: &[String], edits: &[Edit], context: usize) -> Vec<String>{
+    let changes: Vec<usize> = edits.iter().enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::EQUAL(..)))
+        .map(|(index, _)| index)
+        .collect();
+
+    //group changes whose context overlaps
+    let mut hunks = Vec::<(usize, usize)>::new();
+    for index in changes{
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(edits.len());
+
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end))
+        }
+    }
+
+    let mut output = Vec::<String>::new();
+
+    for (start, end) in hunks{
+        //lines before the hunk
+        let old_before = edits[..start].iter().filter(|x| !matches!(x, Edit::INSERT(_))).count();
+        let new_before = edits[..start].iter().filter(|x| !matches!(x, Edit::DELETE(_))).count();
+
+        let old_count = edits[start..end].iter().filter(|x| !matches!(x, Edit::INSERT(_))).count();
+        let new_count = edits[start..end].iter().filter(|x| !matches!(x, Edit::DELETE(_))).count();
+
+        //empty ranges point at the line before
+        let old_start = if old_count > 0 { old_before + 1 } else { old_before };
+        let new_start = if new_count > 0 { new_before + 1 } else { new_before };
+
+        output.push(format!("@@ -{},{} +{},{} @@", old_start, old_count, new_start, new_count));
+
+        for edit in &edits[start..end]{
+            match edit {
+                Edit::EQUAL(old_index, _) => output.push(format!(" {}", old[*old_index])),
+                Edit::DELETE(old_index) => output.push(format!("-{}", old[*old_index])),
+                Edit::INSERT(new_index) => output.push(format!("+{}", new[*new_index]))
+            }
+        }
+    }
+
+    return output
+}
+
+
+///Streams two files and returns the first differing byte offset and its line, both starting at 1.
+///If one file is a prefix of the other the offset is one past the shorter file.
+pub fn first_difference(first: &Path, second: &Path) -> Result<Option<(u64, u64)>, Error>{
+    let mut first = BufReader::new(File::open(first)?).bytes();
+    let mut second = BufReader::new(File::open(second)?).bytes();
+
+    let mut offset: u64 = 1;
+    let mut line: u64 = 1;
+
+    loop{
+        match (first.next().transpose()?, second.next().transpose()?) {
+            (None, None) => return Ok(None),
+            (Some(a), Some(b)) if a == b => {
+                if a == b'\n'{
+                    line += 1;
+                }
+                offset += 1;
+            },
+            _ => return Ok(Some((offset, line)))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String>{
+        return text.lines().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diff_lines(){
+        let old = lines("a\nb\nc\nd");
+        let new = lines("a\nc\nd\ne");
+
+        let edits = diff_lines(&old, &new, false);
+        assert_eq!(edits, vec![Edit::EQUAL(0, 0), Edit::DELETE(1), Edit::EQUAL(2, 1), Edit::EQUAL(3, 2), Edit::INSERT(3)]);
+
+        let edits = diff_lines(&lines("a  b"), &lines(" a b "), true);
+        assert_eq!(edits, vec![Edit::EQUAL(0, 0)]);
+
+        assert_eq!(diff_lines(&[], &lines("x"), false), vec![Edit::INSERT(0)]);
+    }
+
+    #[test]
+    fn test_diff_lines_minimal(){
+        //pseudo random line lists from a small alphabet, checked against the longest common subsequence
+        let mut seed: u64 = 7;
+        let mut next = || { seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); (seed >> 33) as usize };
+
+        for _ in 0..200{
+            let old: Vec<String> = (0..next() % 12).map(|_| (next() % 4).to_string()).collect();
+            let new: Vec<String> = (0..next() % 12).map(|_| (next() % 4).to_string()).collect();
+
+            let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+            for i in 1..=old.len(){
+                for j in 1..=new.len(){
+                    lcs[i][j] = if old[i - 1] == new[j - 1] { lcs[i - 1][j - 1] + 1 } else { lcs[i - 1][j].max(lcs[i][j - 1]) };
+                }
+            }
+
+            let edits = diff_lines(&old, &new, false);
+            let changes = edits.iter().filter(|x| !matches!(x, Edit::EQUAL(..))).count();
+            assert_eq!(changes, old.len() + new.len() - 2 * lcs[old.len()][new.len()]);
+
+            //edits replay old into new
+            let replayed: Vec<String> = edits.iter().filter_map(|x| match x {
+                Edit::EQUAL(i, _) => Some(old[*i].clone()),
+                Edit::INSERT(j) => Some(new[*j].clone()),
+                Edit::DELETE(_) => None
+            }).collect();
+            assert_eq!(replayed, new);
+        }
+    }
+
+    #[test]
+    fn test_unified(){
+        let old = lines("1\n2\n3\n4\n5\n6\n7\n8\n9");
+        let new = lines("1\n2\nthree\n4\n5\n6\n7\n8\n9\n10");
+
+        let edits = diff_lines(&old, &new, false);
+        let hunks = unified(&old, &new, &edits, 1);
+
+        assert_eq!(hunks, vec!["@@ -2,3 +2,3 @@", " 2", "-3", "+three", " 4", "@@ -9,1 +9,2 @@", " 9", "+10"]);
+        assert!(unified(&old, &old, &diff_lines(&old, &old, false), 3).is_empty());
+    }
+}
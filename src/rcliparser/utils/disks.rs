@@ -0,0 +1,75 @@
+use std::io::Error;
+use std::process::Command;
+
+use crate::rcliparser::utils::processes;
+
+
+///Mounted filesystem as reported by the operating system. Sizes are in bytes.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct DiskInfo{
+    pub filesystem: String,
+    pub mount: String,
+    pub total: u64,
+    pub free: u64
+}
+
+
+///Lists logical disks through CIM, since std has no disk space queries on windows.
+#[cfg(windows)]
+pub fn list_disks() -> Result<Vec<DiskInfo>, Error>{
+    let query = "Get-CimInstance Win32_LogicalDisk | Where-Object { $_.Size } | ForEach-Object { \"$($_.DeviceID),$($_.Size),$($_.FreeSpace),$($_.FileSystem)\" }";
+    let output = processes::run(Command::new("powershell").args(["-NoProfile", "-Command", query]))?;
+
+    if !output.status.success(){
+        return Err(Error::other("Invoker Error: Couldn't list disks."))
+    }
+
+    let mut disks = Vec::<DiskInfo>::new();
+
+    for line in String::from_utf8_lossy(&output.stdout).lines(){
+        let parts: Vec<&str> = line.trim().splitn(4, ',').collect();
+        if parts.len() != 4{
+            continue;
+        }
+
+        disks.push(DiskInfo {
+            filesystem: parts[3].to_string(),
+            mount: format!("{}\\", parts[0]),
+            total: parts[1].parse::<u64>().unwrap_or(0),
+            free: parts[2].parse::<u64>().unwrap_or(0)
+        });
+    }
+
+    return Ok(disks)
+}
+
+
+///Lists mounted filesystems with POSIX df output in 1024 byte blocks.
+#[cfg(not(windows))]
+pub fn list_disks() -> Result<Vec<DiskInfo>, Error>{
+    let output = processes::run(Command::new("df").args(["-kP"]))?;
+
+    if !output.status.success(){
+        return Err(Error::other("Invoker Error: Couldn't list disks."))
+    }
+
+    let mut disks = Vec::<DiskInfo>::new();
+
+    //first line is the header
+    for line in String::from_utf8_lossy(&output.stdout).lines().skip(1){
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 6{
+            continue;
+        }
+
+        disks.push(DiskInfo {
+            filesystem: parts[0].to_string(),
+            //mount points can contain spaces
+            mount: parts[5..].join(" "),
+            total: parts[1].parse::<u64>().unwrap_or(0) * 1024,
+            free: parts[3].parse::<u64>().unwrap_or(0) * 1024
+        });
+    }
+
+    return Ok(disks)
+}
@@ -0,0 +1,106 @@
+use std::io::{Error, ErrorKind};
+
+
+///Text encodings read can decode.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Encoding{
+    UTF8,
+    UTF16LE,
+    UTF16BE,
+    LATIN1
+}
+
+impl Encoding{
+    pub fn from_name(name: &str) -> Result<Self, Error>{
+        match name.to_lowercase().replace(['-', '_'], "").as_str() {
+            "utf8" => return Ok(Encoding::UTF8),
+            "utf16" | "utf16le" => return Ok(Encoding::UTF16LE),
+            "utf16be" => return Ok(Encoding::UTF16BE),
+            "latin1" | "iso88591" => return Ok(Encoding::LATIN1),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Unknown encoding {}. Use utf8, utf16, utf16le, utf16be or latin1.", name)))
+        }
+    }
+}
+
+
+///Encoding given by a byte order mark and the length of the mark.
+pub fn detect_bom(bytes: &[u8]) -> Option<(Encoding, usize)>{
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]){
+        return Some((Encoding::UTF8, 3))
+    }
+    if bytes.starts_with(&[0xFF, 0xFE]){
+        return Some((Encoding::UTF16LE, 2))
+    }
+    if bytes.starts_with(&[0xFE, 0xFF]){
+        return Some((Encoding::UTF16BE, 2))
+    }
+    return None
+}
+
+
+///Content is binary if it has a NUL byte in the first 8000 bytes, the same check git uses.
+pub fn is_binary(bytes: &[u8]) -> bool{
+    return bytes.iter().take(8000).any(|x| *x == 0)
+}
+
+
+///Decodes bytes to text. A byte order mark overrides the encoding and invalid sequences become U+FFFD.
+pub fn decode(bytes: &[u8], encoding: Encoding) -> String{
+    let (encoding, bytes) = match detect_bom(bytes) {
+        Some((bom_encoding, length)) => (bom_encoding, &bytes[length..]),
+        None => (encoding, bytes)
+    };
+
+    match encoding {
+        Encoding::UTF8 => return String::from_utf8_lossy(bytes).to_string(),
+        Encoding::LATIN1 => return bytes.iter().map(|x| *x as char).collect(),
+        Encoding::UTF16LE | Encoding::UTF16BE => {
+            let units: Vec<u16> = bytes.chunks(2).map(|x| {
+                let pair = [x[0], *x.get(1).unwrap_or(&0)];
+                match encoding {
+                    Encoding::UTF16LE => u16::from_le_bytes(pair),
+                    _ => u16::from_be_bytes(pair)
+                }
+            }).collect();
+
+            return String::from_utf16_lossy(&units)
+        }
+    }
+}
+
+
+///One xxd style row: offset, up to 16 bytes in groups of two and their printable characters.
+pub fn hex_row(offset: usize, bytes: &[u8]) -> String{
+    let mut hex = String::new();
+
+    for (index, byte) in bytes.iter().enumerate(){
+        if index > 0 && index % 2 == 0{
+            hex.push(' ');
+        }
+        hex.push_str(&format!("{:02x}", byte));
+    }
+
+    let text: String = bytes.iter().map(|x| if x.is_ascii_graphic() || *x == b' ' { *x as char } else { '.' }).collect();
+
+    return format!("{:08x}: {:<39}  {}", offset, hex, text)
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_decode(){
+        assert_eq!(decode(&[0xFF, 0xFE, b'h', 0, b'i', 0], Encoding::UTF8), "hi");
+        assert_eq!(decode(&[0, b'h', 0, b'i'], Encoding::UTF16BE), "hi");
+        assert_eq!(decode(&[b'c', 0xE9], Encoding::LATIN1), "c\u{e9}");
+        assert_eq!(decode(&[b'c', 0xE9], Encoding::UTF8), "c\u{fffd}");
+        assert!(is_binary(&[b'a', 0, b'b']));
+    }
+
+    #[test]
+    fn test_hex_row(){
+        assert_eq!(hex_row(16, b"Hello world.\n"), "00000010: 4865 6c6c 6f20 776f 726c 642e 0a         Hello world..");
+    }
+}
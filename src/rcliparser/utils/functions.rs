@@ -1,10 +1,32 @@
-use std::{any::Any, fmt::format, fs::{self, DirBuilder, DirEntry, OpenOptions}, io::{self, BufRead, Error, ErrorKind, Write}, os::windows::fs::MetadataExt, path::{Path, PathBuf}, vec};
+use std::{collections::BTreeSet, fs::{self, DirBuilder, FileTimes, OpenOptions}, io::{self, BufRead, Error, ErrorKind, IsTerminal, Read, Seek, SeekFrom, Write}, path::{Path, PathBuf}, sync::{atomic::{AtomicBool, Ordering as AtomicOrdering}, Arc}, thread, time::{Duration, SystemTime, UNIX_EPOCH}, vec};
 use regex::Regex;
 
-use crate::{rcliparser::objects::data_types::Data, rcliterminal::terminal::Terminal};
+use crate::{rclilogger::logger::Logger, rcliparser::objects::data_types::{Data, TreeNode}, rcliterminal::terminal::Terminal};
+use crate::rcliparser::objects::grammar_objects::CommandType;
+use crate::rcliparser::lexical_analyzer;
 
+use crate::rcliparser::utils::archives;
+use crate::rcliparser::utils::copying::{self, CopyOptions, CopyReport, Overwrite};
+use crate::rcliparser::utils::diff;
+use crate::rcliparser::utils::disks;
+use crate::rcliparser::utils::encodings::{self, Encoding};
+use crate::rcliparser::utils::hashes::{self, HashAlgorithm};
+use crate::rcliparser::utils::processes;
+use crate::rcliparser::utils::search::{self, FindOptions, LineMatch, SearchOptions};
+use crate::rcliparser::utils::trash;
+
+#[cfg(windows)]
+use std::os::windows::fs::MetadataExt;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+#[cfg(windows)]
 use crate::rcliparser::utils::windows::windows_file_attributes;
 
+#[cfg(windows)]
+use std::os::windows::fs as os_fs;
+#[cfg(unix)]
+use std::os::unix::fs as os_fs;
+
 
 ///Shows the users home directory. Returns path.
 pub fn home(terminal_instance: &mut Terminal) -> Result<Data, Error>{
@@ -25,32 +47,76 @@ pub fn echo(input: &String) -> Result<Data, Error>{
 }
 
 
-///Creates a file at the given path. Returns file.
-pub fn touch(file_path: &Path, data: Option<Vec<String>>) -> Result<Data, Error>{
-    //could not need the open() clause unless pipelining
-    let file_operation = OpenOptions::new().write(true).read(true).create(true).open(file_path);
+///Creates a file at the given path or sets the access and modification times of an existing one. Returns file.
+pub fn touch(file_path: &Path, time: SystemTime) -> Result<Data, Error>{
+    if file_path.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot touch a directory."))
+    }
 
-    match file_operation{
-        Ok(mut file) => {
-            if data.is_some(){
-                for line in data.unwrap(){
-                    match file.write_all(line.as_bytes()){
-                        Ok(_) => {
-                            continue;
-                        },
-                        Err(error) => {
-                            return Err(error)
-                        }
-                    }
-                }
-            }
-            return Ok(Data::PathData(file_path.to_path_buf()));
+    //existing content is kept
+    let file = OpenOptions::new().write(true).create(true).truncate(false).open(file_path)?;
+    file.set_times(FileTimes::new().set_accessed(time).set_modified(time))?;
 
-        },
-        Err(error) => {
-            return Err(error);
-        }
+    return Ok(Data::PathData(file_path.to_path_buf()))
+}
+
+
+///Parses a time given as YYYY-MM-DD, YYYY-MM-DD HH:MM[:SS] (a T may separate date and time) or @seconds since the epoch. Times are UTC.
+pub fn parse_time(value: &str) -> Result<SystemTime, Error>{
+    let invalid = || Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Invalid time {}. Use YYYY-MM-DD [HH:MM[:SS]] or @seconds.", value));
+
+    if let Some(seconds) = value.strip_prefix('@'){
+        let seconds = seconds.parse::<u64>().map_err(|_| invalid())?;
+        return Ok(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    let (date, time) = match value.trim().split_once([' ', 'T']) {
+        Some((date, time)) => (date, time),
+        None => (value.trim(), "00:00")
+    };
+
+    let date: Vec<u64> = date.split('-').map(|x| x.parse::<u64>()).collect::<Result<Vec<u64>, _>>().map_err(|_| invalid())?;
+    let time: Vec<u64> = time.split(':').map(|x| x.parse::<u64>()).collect::<Result<Vec<u64>, _>>().map_err(|_| invalid())?;
+
+    if date.len() != 3 || !(2..=3).contains(&time.len()){
+        return Err(invalid())
+    }
+    let (year, month, day) = (date[0], date[1], date[2]);
+    let (hour, minute, second) = (time[0], time[1], *time.get(2).unwrap_or(&0));
+
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59{
+        return Err(invalid())
+    }
+
+    //days since the epoch of a proleptic gregorian date, with march as the first month so leap days come last
+    let (year, month) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    return Ok(UNIX_EPOCH + Duration::from_secs(days * 86400 + hour * 3600 + minute * 60 + second))
+}
+
+
+///Writes lines to a file, each followed by a newline. Overwriting replaces the file atomically. Returns file.
+pub fn write(file_path: &Path, lines: Vec<String>, append: bool) -> Result<Data, Error>{
+    if file_path.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot write to a directory."))
+    }
+
+    let content: String = lines.into_iter().map(|x| x + "\n").collect();
+
+    if append{
+        let mut file = OpenOptions::new().append(true).create(true).open(file_path)?;
+        file.write_all(content.as_bytes())?;
+    }
+    else{
+        write_atomic(file_path, content.as_bytes())?;
     }
+
+    return Ok(Data::PathData(file_path.to_path_buf()))
 }
 
 
@@ -99,137 +165,324 @@ pub fn remove(path: &Path, recursive: bool) -> Result<Data, Error>{
 }
 
 
+///Entries a recursive remove deletes before it asks for confirmation.
+const LARGE_TREE: usize = 100;
+
+
+///Asks before recursively removing a directory with more than LARGE_TREE entries.
+///Only asks on a terminal, scripts are never blocked. Returns if removing should go on.
+pub fn confirm_remove(path: &Path) -> Result<bool, Error>{
+    if !path.is_dir() || !io::stdin().is_terminal(){
+        return Ok(true)
+    }
+
+    //counting stops past the limit, a huge tree isn't walked twice
+    let (entries, _) = tree_stats(path, Some(LARGE_TREE));
+    if entries <= LARGE_TREE{
+        return Ok(true)
+    }
+    return confirm(&format!("Remove {} with more than {} entries?", path.display(), LARGE_TREE))
+}
+
+
+///Lists what remove would delete without deleting anything.
+pub fn remove_dry_run(path: &Path, recursive: bool) -> Result<Data, Error>{
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Object doesn't exist."))
+    };
+
+    if metadata.is_dir() && !recursive && fs::read_dir(path)?.next().is_some(){
+        return Err(Error::new(ErrorKind::DirectoryNotEmpty, format!("Invoker Error: {} isn't empty, use -r.", path.display())))
+    }
+
+    let (entries, bytes) = tree_stats(path, None);
+    return Ok(Data::VecStringData(vec![format!("Would remove {} ({} entries, {})", path.display(), entries, format_size(bytes))]))
+}
+
+
+///Moves a file or directory to the trash instead of deleting it. Returns the original path.
+pub fn trash(path: &Path, recursive: bool) -> Result<Data, Error>{
+    if path.is_dir() && !path.is_symlink() && !recursive{
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: {} is a directory, use -r.", path.display())))
+    }
+
+    let entry = trash::put(path)?;
+    return Ok(Data::PathData(entry.original))
+}
+
+
+///Trashed items with their deletion time, id and original path.
+pub fn trash_list() -> Result<Data, Error>{
+    let mut rows = vec![vec![String::from("DELETED"), String::from("ID"), String::from("ORIGINAL")]];
+
+    for entry in trash::list()?{
+        rows.push(vec![format_time(entry.deleted), entry.id, entry.original.display().to_string()]);
+    }
+    return Ok(Data::TableData(rows))
+}
+
+
+///Restores a trashed item to its original path. Returns the restored path.
+pub fn trash_restore(name: Option<&str>) -> Result<Data, Error>{
+    let entry = trash::restore(name)?;
+    return Ok(Data::PathData(entry.original))
+}
+
+
+///Permanently deletes the trashed items.
+pub fn trash_empty() -> Result<Data, Error>{
+    let count = trash::empty()?;
+    return Ok(Data::VecStringData(vec![format!("Deleted {} items from the trash.", count)]))
+}
+
+
 ///Copies the content of either a file or a directory. This is more powerful over move
 ///due to the fact that it can copy without requiring permissions (on Windows) whereas 
-///move requires.
-///Returns the destination file or directory.
-pub fn copy(path: &Path, destination: &Path, force: bool, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+///move requires. An existing destination directory receives the origin under its own name.
+///Returns the destination file or directory, or an error listing every entry that couldn't be copied.
+pub fn copy(path: &Path, destination: &Path, options: &CopyOptions) -> Result<Data, Error>{
+    let (target, report) = copy_report(path, destination, options)?;
+
+    match report.error() {
+        Some(error) => return Err(error),
+        None => return Ok(Data::PathData(target))
+    }
+}
 
-    //if origin doesnt exist error
-    if !path.try_exists()?{
-        return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Path |{path:?}| doesn't exist."))
+
+///Moves files or directories. Several origins are moved into the destination directory.
+///Every origin is renamed, and only when that fails because the destination is on another device
+///it is copied and removed after every entry was copied and verified.
+///Returns destination path.
+pub fn r#move(paths: &[PathBuf], destination: &Path, options: &CopyOptions) -> Result<Data, Error>{
+    if paths.len() > 1 && !destination.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Moving several paths needs an existing directory, {} isn't one.", destination.display())))
     }
 
-    //if destination exists
-    if destination.try_exists()?{
-        //and origin is a directory
-        if path.is_dir(){
-            let mut destination_path = terminal_instance.get_current_directory().join(destination);
-            destination_path.push(path.components().last().unwrap().as_os_str());
-            
-            return copy_dir(path, Some(destination_path.as_path()));
-        }
-        //else origin is file/symlink/hardlink
-        else{
-            if force{
-                let file_path = terminal_instance.get_current_directory().join(destination);
-                
-                //create file in destination
-                let _ = OpenOptions::new().write(true).read(true).create(true).open(file_path.clone())?;
-                
-                //copy data
-                match fs::copy(path, file_path.clone()){
-                    Ok(_) => {
-                        return Ok(Data::PathData(file_path))
-                    },
-                    Err(error) => {
-                        return Err(error)
-                    },
-                }
-            }
-            else{
-                return Err(Error::new(ErrorKind::PermissionDenied, "Invoker Error: Destination object exists, use -force to overwrite."))
-            }
-        
+    let mut moved = Vec::<PathBuf>::new();
+    let mut errors = Vec::<(PathBuf, Error)>::new();
+
+    for path in paths{
+        match move_path(path, destination, options) {
+            Ok(Some(target)) => moved.push(target),
+            Ok(None) => {},
+            Err(error) => errors.push((path.clone(), error))
         }
     }
-    //if destination doesn't exist
-    else{
-        //if origin is path simply copy it (with data of course)
-        if path.is_file(){
-            match fs::copy(path, destination){
-                Ok(_) => {
-                    return Ok(Data::PathData(destination.to_path_buf()))
-                },
-                Err(error) => {
-                    return Err(error)
-                },
-            }
-        }
-        //if origin is dir copy all
-        else{
-            return copy_dir(path, Some(destination));
-        }
+
+    if errors.len() == 1 && paths.len() == 1{
+        return Err(errors.pop().unwrap().1)
+    }
+    if !errors.is_empty(){
+        let mut lines: Vec<String> = errors.iter().map(|(path, error)| format!("{}: {}", path.display(), error)).collect();
+        lines.push(format!("Invoker Error: {} of {} paths failed to move.", errors.len(), paths.len()));
+
+        return Err(Error::other(lines.join("\n")))
+    }
+
+    match (paths.len(), moved.pop()) {
+        (1, Some(target)) => return Ok(Data::PathData(target)),
+        _ => return Ok(Data::PathData(destination.to_path_buf()))
     }
 }
 
 
-///Moves and renames a file or directory.
-///If destination doesn't exist it renames a file. If it does exist it copies it.
-///Returns destination path.
-pub fn r#move(path: &Path, destination: &Path, force: bool, terminal_instance: &mut Terminal) -> Result<Data, Error>{
-    let path_exists = path.try_exists()? | path.exists();
-    let dest_exists = destination.try_exists()?;
-    //simple rename
-    let mut result: Result<Data, Error>= Err(Error::new(ErrorKind::Other, "Invoker Error: Error occured while moving."));
+///Moves one path. Returns None if it was skipped because the destination exists.
+fn move_path(path: &Path, destination: &Path, options: &CopyOptions) -> Result<Option<PathBuf>, Error>{
+    if fs::symlink_metadata(path).is_err(){
+        return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: Path {} doesn't exist.", path.display())))
+    }
 
-    if path_exists{
-        let origin_canonicalized = path.canonicalize()?;
-        let destintion_canonicalized = PathBuf::from(path.canonicalize().unwrap()).join(destination);
+    let target = copy_target(path, destination)?;
+    if is_inside(path, &target)?{
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot move a directory into itself."))
+    }
 
-        //Origin and destination are on a different hierarchical level so we copy(because rename has problems)
-        if !destintion_canonicalized.components().last().eq(&origin_canonicalized.components().last()){
-            result = copy(path, destination, force, terminal_instance);
-        }
-        //Origin and destination are on the same hierarchy
-        else{
-            if !dest_exists{
-                if fs::rename(path, destination).is_ok(){
-                    result = Ok(Data::PathData(destination.into()))
-                }
-            }
-            else{
-                result = copy(path, destination, force, terminal_instance);
-            }
+    if let Ok(metadata) = fs::symlink_metadata(&target){
+        if metadata.is_dir(){
+            return Err(Error::new(ErrorKind::AlreadyExists, format!("Invoker Error: {} is a directory.", target.display())))
         }
 
-        if result.is_ok(){
-            //If result is valid, cd to father dir
-            let _ = traverse_directory(Path::new(".."), terminal_instance);
-            //Delete underlying dir
-            let _ = remove(&origin_canonicalized, true);
-            
-            return Ok(result.unwrap())
+        match options.overwrite {
+            Overwrite::DENY => return Err(Error::new(ErrorKind::AlreadyExists, "Invoker Error: Destination object exists, use -f to overwrite.")),
+            Overwrite::NOCLOBBER => return Ok(None),
+            Overwrite::INTERACTIVE if !confirm(&format!("Overwrite {}?", target.display()))? => return Ok(None),
+            _ => {}
         }
     }
 
-    return Err(result.unwrap_err())
+    match fs::rename(path, &target) {
+        Ok(_) => return Ok(Some(target)),
+        Err(error) if !is_cross_device(&error) => return Err(error),
+        Err(_) => {}
+    }
+
+    //another device, existing files were already confirmed
+    let copy_options = CopyOptions { overwrite: Overwrite::FORCE, ..options.clone() };
+    let report = copying::copy_tree(path, &target, &copy_options);
+
+    if let Some(error) = report.error(){
+        return Err(error)
+    }
+    if !report.verify(){
+        return Err(Error::other("Invoker Error: Copy couldn't be verified, the origin was kept."))
+    }
+
+    //a moved link is removed, not what it points to
+    match fs::symlink_metadata(path)?.is_dir() {
+        true => fs::remove_dir_all(path)?,
+        false => fs::remove_file(path)?
+    }
+    return Ok(Some(target))
 }
 
-///Reads the content of a file to terminal
-///Returns content as string.
-pub fn read(path: &Path) -> Result<Data, Error>{
-    if path.exists() | path.try_exists()?{
-        if path.is_dir(){
-            return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot read directory. Use ls instead."))
-        }
-        let file = OpenOptions::new().write(true).read(true).open(path);
 
-        if file.is_ok(){
-            let lines = io::BufReader::new(file.unwrap()).lines();
+///Rename failed because origin and destination are on different devices (EXDEV, ERROR_NOT_SAME_DEVICE).
+fn is_cross_device(error: &Error) -> bool{
+    #[cfg(windows)]
+    {
+        return error.raw_os_error() == Some(17)
+    }
+    #[cfg(unix)]
+    {
+        return error.raw_os_error() == Some(18)
+    }
+}
 
-            let mut output_string = Vec::<String>::new();
-            
-            for line in lines.flatten(){
-                output_string.push(line);
-            }
-            return Ok(Data::VecStringData(output_string));
+
+///Copies path to destination, or into it if it is a directory. Returns the copy and what happened to every entry.
+fn copy_report(path: &Path, destination: &Path, options: &CopyOptions) -> Result<(PathBuf, CopyReport), Error>{
+    if fs::symlink_metadata(path).is_err(){
+        return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: Path {} doesn't exist.", path.display())))
+    }
+
+    let target = copy_target(path, destination)?;
+    if is_inside(path, &target)?{
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot copy a directory into itself."))
+    }
+    //overwriting would remove the origin before it is read
+    if is_same_file(path, &target){
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: {} and {} are the same file.", path.display(), target.display())))
+    }
+
+    let report = copying::copy_tree(path, &target, options);
+    return Ok((target, report))
+}
+
+
+///Destination of path. An existing destination directory receives it under its own name.
+fn copy_target(path: &Path, destination: &Path) -> Result<PathBuf, Error>{
+    //links keep their own name and aren't resolved, so dangling ones work too. . and .. have no name
+    let name = match path.file_name() {
+        Some(name) => Some(name.to_os_string()),
+        None => path.canonicalize()?.file_name().map(|x| x.to_os_string())
+    };
+
+    match (destination.is_dir(), name) {
+        (true, Some(name)) => return Ok(destination.join(name)),
+        _ => return Ok(destination.to_path_buf())
+    }
+}
+
+
+///If target is path itself or somewhere inside the directory path.
+fn is_inside(path: &Path, target: &Path) -> Result<bool, Error>{
+    if !fs::symlink_metadata(path)?.is_dir(){
+        return Ok(false)
+    }
+
+    //first existing ancestor of the target decides where it really is
+    let mut existing = target;
+    while !existing.exists(){
+        existing = match existing.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new(".")
+        };
+    }
+    return Ok(existing.canonicalize()?.starts_with(path.canonicalize()?))
+}
+
+///If target exists and is path, the file path links to, or a hard link to either.
+fn is_same_file(path: &Path, target: &Path) -> bool{
+    let target_metadata = match fs::symlink_metadata(target) {
+        Ok(metadata) => metadata,
+        Err(_) => return false
+    };
+
+    #[cfg(unix)]
+    {
+        let id = |x: &fs::Metadata| (x.dev(), x.ino());
+        return [fs::symlink_metadata(path), fs::metadata(path)].iter().flatten().any(|x| id(x) == id(&target_metadata))
+    }
+    //file indexes aren't available on stable, so hard links aren't caught
+    #[cfg(windows)]
+    {
+        let _ = target_metadata;
+        return match (path.canonicalize(), target.canonicalize()) {
+            (Ok(path), Ok(target)) => path == target,
+            _ => false
         }
-        return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Error while opening file."));
+    }
+}
+
 
+///Reads a file as text. Binary files are refused, text is decoded with the given encoding unless it has a byte order mark
+///and invalid sequences are replaced instead of dropping their lines. Returns lines, optionally numbered.
+pub fn read(path: &Path, encoding: Option<Encoding>, line_numbers: bool) -> Result<Data, Error>{
+    if !path.exists(){
+        return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
+    }
+    if path.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot read directory. Use ls instead."))
+    }
 
+    let content = match fs::read(path) {
+        Ok(content) => content,
+        Err(_) => return Err(Error::new(ErrorKind::PermissionDenied, "Invoker Error: Error while opening file."))
+    };
+
+    //utf-16 text is full of NUL bytes, so only unmarked content without an explicit encoding is checked
+    if encoding.is_none() && encodings::detect_bom(&content).is_none() && encodings::is_binary(&content){
+        return Err(Error::new(ErrorKind::InvalidData, format!("Invoker Error: {} is a binary file. Use hexdump instead.", path.display())))
     }
-    return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
+
+    let text = encodings::decode(&content, encoding.unwrap_or(Encoding::UTF8));
+    let lines = text.lines().enumerate().map(|(index, line)| {
+        match line_numbers {
+            true => format!("{:>6}  {}", index + 1, line),
+            false => line.to_string()
+        }
+    });
+
+    return Ok(Data::VecStringData(lines.collect()))
+}
+
+
+///xxd style dump of a file or piped content, 16 bytes per row. Rows limits the output and only that much of the file is read.
+pub fn hexdump(path: Option<&Path>, piped_content: Option<Vec<u8>>, rows: Option<usize>) -> Result<Data, Error>{
+    let content = match (path, piped_content) {
+        (Some(path), _) => {
+            if !path.is_file(){
+                return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
+            }
+            let mut content = Vec::<u8>::new();
+            let file = fs::File::open(path)?;
+
+            match rows {
+                Some(rows) => file.take(rows as u64 * 16).read_to_end(&mut content)?,
+                None => io::BufReader::new(file).read_to_end(&mut content)?
+            };
+            content
+        },
+        (None, Some(content)) => {
+            content.into_iter().take(rows.map_or(usize::MAX, |x| x.saturating_mul(16))).collect()
+        },
+        (None, None) => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide a file or piped data."))
+    };
+
+    let output = content.chunks(16).enumerate().map(|(index, row)| encodings::hex_row(index * 16, row));
+    return Ok(Data::VecStringData(output.collect()))
 }
 
 
@@ -242,15 +495,17 @@ pub fn list(dir_path: &Path, hidden: bool, recursive: bool) -> Result<Data, Erro
             for path in paths{
                 let dir_path = path.unwrap().path();
 
-                match fs::metadata(dir_path.clone()) {
+                //links are not followed
+                match fs::symlink_metadata(dir_path.clone()) {
                     Ok(meta) => {
-                        let attributes = meta.file_attributes();
-
-                        let entry_attributes = windows_file_attributes::match_attributes(attributes);
-                        
-                        let canonicalized_path = match dir_path.canonicalize() {
-                            Ok(path) => path,
-                            Err(_) => dir_path.clone(),
+                        let canonicalized_path = if meta.file_type().is_symlink(){
+                            display_link(&dir_path)
+                        }
+                        else{
+                            match dir_path.canonicalize() {
+                                Ok(path) => path,
+                                Err(_) => dir_path.clone(),
+                            }
                         };
 
                         //if hidden is true push everything
@@ -258,21 +513,15 @@ pub fn list(dir_path: &Path, hidden: bool, recursive: bool) -> Result<Data, Erro
                             outputbuffer.push(canonicalized_path);
                         }
                         //else if hidden is false, then append dirs that arent marked as hidden
-                        else if !entry_attributes.contains(&windows_file_attributes::WindowsAttributes::HIDDEN){
+                        else if !is_hidden(&dir_path, &meta, false){
                             outputbuffer.push(canonicalized_path);
                         }
                         
-                        if recursive & dir_path.is_dir(){
+                        if recursive & meta.is_dir(){
                             match list(&dir_path, hidden, recursive)?{
                                 Data::DirPathData(path_vec) => {
                                     path_vec.iter().for_each(|y| {
-
-                                        let normalized_path = match y.canonicalize() {
-                                            Ok(path) => path,
-                                            Err(_) => y.to_path_buf(),
-                                        };
-
-                                        outputbuffer.push(normalized_path)
+                                        outputbuffer.push(y.to_path_buf())
                                     })
                                 },
                                 _ => unreachable!()
@@ -307,190 +556,1309 @@ pub fn traverse_directory(path: &Path, terminal_instance: &mut Terminal) -> Resu
     }
 }
 
-///For given data returns match.
-///Data can be either a dir or path.
-///Therefore matches are either files or Strings.
-pub fn global_regex(input: &dyn Any, regex_string: &String)  -> Result<Option<Data>, io::Error> {
-    let pattern_string = format!(r"\b\w*{}\w*\b", regex_string);
-    let pattern = Regex::new(pattern_string.as_str()).unwrap();
-    
-    if let Some(text_input) = input.downcast_ref::<String>() {
-        
-        if pattern.is_match(text_input.as_str()){
-            return Ok(Some(Data::StringData(text_input.to_string())))
-        }
-        
-        return Ok(None)
-    } else if let Some(path_input) = input.downcast_ref::<PathBuf>() {
-        
-        if path_input.try_exists()?{
-            if path_input.is_dir(){
-                let mut output_string = Vec::<String>::new();
-                
-                let dir_entries = match list(path_input.as_path(), true, false)? {
-                    Data::DirPathData(entries) => entries,
-                    _ => vec![],
-                };
-    
-                for entry in dir_entries{
-                    let entry_name = entry.as_path().to_str().unwrap();
-                    if pattern.is_match(entry_name){
-                        output_string.push(format(format_args!("[ {} ]", entry_name)));
-                    }
-                }
-    
-                return Ok(Some(Data::VecStringData(output_string)))
-            }
-            else if path_input.is_file(){
-                //todo! change with read?
-                let res = read(path_input);
-    
-                if res.is_ok(){
-                    let mut output_string = Vec::<String>::new();
-    
-                    match res.unwrap(){
-                        Data::VecStringData(vector) => {
-                            for line in vector{
-                                if pattern.is_match(line.as_str()){
-                                    output_string.push(format(format_args!("[ {} ]", line)));
-                                }
-                            }
-                            return Ok(Some(Data::VecStringData(output_string)))
-                        },
-                        _ => unreachable!()
-                    }
-                }
-                return Err(res.unwrap_err())
+
+///Changes back to the directory before the last change (cd -). Returns the new current directory.
+pub fn previous_directory(terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    let previous = match terminal_instance.get_previous_directory() {
+        Some(previous) => previous,
+        None => return Err(Error::new(ErrorKind::NotFound, "Invoker Error: No previous directory."))
+    };
+
+    traverse_directory(&previous, terminal_instance)?;
+    return Ok(Data::PathData(terminal_instance.get_current_directory()))
+}
+
+
+///Changes to path and saves the current directory on the stack. Without a path the current directory
+///is swapped with the top of the stack. Returns the stack.
+pub fn pushd(path: Option<&Path>, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    let current = terminal_instance.get_current_directory();
+
+    match path {
+        Some(path) => {
+            traverse_directory(path, terminal_instance)?;
+        },
+        None => {
+            let top = match terminal_instance.pop_directory() {
+                Some(top) => top,
+                None => return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Directory stack is empty."))
+            };
+            //the stack is left as it was if the directory is gone
+            if let Err(error) = traverse_directory(&top, terminal_instance){
+                terminal_instance.push_directory(top);
+                return Err(error)
             }
         }
-        Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
-    } 
-    else {
-        return Err(Error::new(ErrorKind::InvalidData, "Invoker Error: Invalid data type."))
     }
+
+    terminal_instance.push_directory(current);
+    return dirs(terminal_instance)
 }
 
 
-///Searches the given (or current) directory for an object
-pub fn find(target: &String, target_directory: &Path) -> Result<Option<Data>, Error>{
-    let pattern_string = format!(r"\b\w*{}\w*\b", target.to_lowercase());
-    let pattern = Regex::new(pattern_string.as_str()).unwrap();
+///Changes to the directory on top of the stack and removes it. Returns the stack.
+pub fn popd(terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    let top = match terminal_instance.pop_directory() {
+        Some(top) => top,
+        None => return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Directory stack is empty."))
+    };
 
-    match fs::read_dir(target_directory) {
-        Ok(paths) => {
-            let mut lower_level: Vec<PathBuf> = vec![];
-
-            for object in paths{
-                let object_path = object.unwrap();
-                let object_parts: Vec<String> = object_path.file_name().into_string().unwrap().split(".").map(|x| x.to_string()).collect();
-                
-                if pattern.is_match(object_parts.first().unwrap().to_lowercase().as_str()){
-                    return Ok(Some(Data::PathData(object_path.path())))
-                }
+    traverse_directory(&top, terminal_instance)?;
+    return dirs(terminal_instance)
+}
 
 
-                match fs::metadata(object_path.path()) {
-                    Ok(meta) => {
-                        let attributes = meta.file_attributes();
-                        
-                        let entry_attributes = windows_file_attributes::match_attributes(attributes);
+///Current directory followed by the stack, most recently pushed first.
+pub fn dirs(terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    let mut directories = vec![terminal_instance.get_current_directory()];
+    directories.extend(terminal_instance.get_directory_stack().into_iter().rev());
 
-                        if entry_attributes.contains(&windows_file_attributes::WindowsAttributes::DIRECTORY) && 
-                        !entry_attributes.contains(&windows_file_attributes::WindowsAttributes::FILE_SYSTEM){
-                            
-                            lower_level.push(object_path.path())
-                        }
-                    },
-                    Err(error) => {
-                        return Err(error)
-                    }
-                }
+    return Ok(Data::DirPathData(directories))
+}
+
+
+///Replaces a leading ~ (~, ~/path or ~\path) with the home directory. Other values are returned as they are.
+pub fn expand_tilde(value: &str, home: &Path) -> String{
+    if value == "~"{
+        return home.display().to_string()
+    }
+    match value.strip_prefix("~/").or(value.strip_prefix("~\\")) {
+        Some(rest) => return home.join(rest).display().to_string(),
+        None => return value.to_string()
+    }
+}
+
+
+///Searches piped lines or files for a pattern. Directories are searched recursively.
+///File matches are returned as structured results. With context lines or counts the output is text:
+///file:line:text for matches, file-line-text for context and -- between context groups.
+pub fn grep(pattern: &str, paths: Vec<PathBuf>, piped_lines: Option<Vec<String>>, options: &SearchOptions, line_numbers: bool, count: bool) -> Result<Data, Error>{
+    let regex = search::build_regex(pattern, options)?;
+
+    let results: Vec<(Option<PathBuf>, Vec<LineMatch>)> = if paths.is_empty() {
+        let lines = piped_lines.unwrap_or_default();
+        vec![(None, search::search_lines(&lines, &regex, options))]
+    }
+    else{
+        let mut files = Vec::<PathBuf>::new();
+
+        for path in paths{
+            if !path.try_exists()?{
+                return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: Invalid path {}.", path.display())))
+            }
+            if path.is_dir(){
+                files.append(&mut search::collect_files(&path, options)?);
             }
-            let mut results : Vec<Option<Data>> = vec![];
-            
-            for object in lower_level{
+            else{
+                files.push(path);
+            }
+        }
 
-                match find(target, &object) {
-                    Ok(res) => {
-                        results.push(res)
-                    },
-                    Err(error) => {
-                        return Err(error)
-                    },
-                };
+        search::search_files(&files, &regex, options).into_iter().map(|(path, matches)| (Some(path), matches)).collect()
+    };
+
+    let found = results.iter().any(|(_, matches)| matches.iter().any(|x| x.is_match));
+    if !found && !count{
+        return Err(Error::new(ErrorKind::NotFound, "Invoker Error: No match found."))
+    }
+
+    let context = options.before > 0 || options.after > 0;
+
+    //structured results
+    if !count && !context && results.iter().all(|(path, _)| path.is_some()){
+        let matches = results.into_iter()
+            .flat_map(|(path, matches)| {
+                let path = path.unwrap();
+                matches.into_iter().map(move |x| (path.clone(), x.line_number, x.line))
+            })
+            .collect();
+
+        return Ok(Data::MatchData(matches))
+    }
+
+    let mut output_string = Vec::<String>::new();
+
+    for (path, matches) in results{
+        let name = path.map(|x| x.display().to_string());
+
+        if count{
+            let matches = matches.iter().filter(|x| x.is_match).count();
+            match &name {
+                Some(name) => output_string.push(format!("{}:{}", name, matches)),
+                None => output_string.push(matches.to_string())
             }
+            continue;
+        }
 
-            for res in results{
-                if res.is_some(){
-                    return Ok(Some(res.as_ref().unwrap().clone()))
-                }
+        let mut last_line: Option<usize> = None;
+
+        for result in matches{
+            if context && !output_string.is_empty() && last_line.is_none_or(|x| x + 1 != result.line_number){
+                output_string.push(String::from("--"));
             }
-            
-            return Ok(None)
+            last_line = Some(result.line_number);
 
-        },
-        Err(error) => {
-            return Err(error)
+            let separator = if result.is_match { ':' } else { '-' };
+
+            match &name {
+                Some(name) => output_string.push(format!("{}{}{}{}{}", name, separator, result.line_number, separator, result.line)),
+                None if line_numbers => output_string.push(format!("{}{}{}", result.line_number, separator, result.line)),
+                None => output_string.push(result.line)
+            }
         }
     }
 
+    return Ok(Data::VecStringData(output_string))
 }
 
 
-///Exits RCli
-pub fn exit() -> Result<Data, io::Error> {
-    Ok(Data::StatusData(1))
-}
+///Finds every entry under target_directory matching the options. Returns None if nothing matches.
+pub fn find(target_directory: &Path, options: &FindOptions) -> Result<Option<Data>, Error>{
+    if !target_directory.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Destination isn't a directory."))
+    }
 
-///Processes invalid commands
-pub fn invalid() -> Result<Data, io::Error> {
-    Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Invalid command."))
-}
+    let results = search::find_entries(target_directory, options)?;
 
+    if results.is_empty(){
+        return Ok(None)
+    }
+    return Ok(Some(Data::DirPathData(results)))
+}
 
-/*
-    HELPER FUNCTIONS
-*/
 
-///Helper function to recursively copy a directory with its content.
-///Mimics DFS algorithms. Used in cp/copy and in move.
-///Returns top level path of copied dir.
-fn copy_dir(original_path: &Path, destination: Option<&Path>) -> Result<Data, Error>{
-    
-    if destination.is_some(){
-        fs::create_dir_all(destination.unwrap()).ok();
+///Creates a hard link, or a symbolic link if symbolic is set. Returns path of the link.
+///Hard links can only point to files, directories require a symbolic link.
+pub fn link(target: &Path, link_path: &Path, symbolic: bool) -> Result<Data, Error>{
+    if !target.try_exists()?{
+        return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: Target {} doesn't exist.", target.display())))
     }
 
-    let mut entries: Vec<DirEntry> = vec![];
-    
-    match fs::read_dir(original_path){
-        Ok(dir_paths) => {
-            //Collect all current dir entries
-            for entry in dir_paths.map(|entry| entry.unwrap()){
-                entries.push(entry);
-            }
+    if link_path.symlink_metadata().is_ok(){
+        return Err(Error::new(ErrorKind::AlreadyExists, format!("Invoker Error: {} already exists.", link_path.display())))
+    }
 
-            //Double for loop is needed because some elements are skipped when the matching
-            //is done in parallel with the loop.
+    if symbolic{
+        //relative targets are resolved from the link location
+        let target_path = target.canonicalize()?;
 
-            //for every entry found
-            for entry in entries{
-                let new_path = destination.unwrap().join(entry.file_name());
-                    
-                if entry.file_type().unwrap().is_file(){
-                    let _ = fs::copy(&entry.path(), new_path);
-                }
-                else if entry.file_type().unwrap().is_dir(){
-                    let _ = copy_dir(entry.path().as_path(), Some(&new_path));
-                }
-                else{
-                    let _ = fs::copy(&entry.path(), new_path);
-                }
+        #[cfg(windows)]
+        {
+            if target_path.is_dir(){
+                os_fs::symlink_dir(&target_path, link_path)?;
+            }
+            else{
+                os_fs::symlink_file(&target_path, link_path)?;
             }
-            return Ok(Data::PathData(destination.unwrap().to_path_buf()))
         }
-        Err(error) => {
-            return Err(error)
+        #[cfg(unix)]
+        {
+            os_fs::symlink(&target_path, link_path)?;
         }
     }
-}
\ No newline at end of file
+    else{
+        if target.is_dir(){
+            return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot hard link a directory. Use -s instead."))
+        }
+        fs::hard_link(target, link_path)?;
+    }
+
+    return Ok(Data::PathData(link_path.to_path_buf()))
+}
+
+
+///Shows the target of a symbolic link (or junction).
+pub fn readlink(path: &Path) -> Result<Data, Error>{
+    let metadata = fs::symlink_metadata(path)?;
+
+    if !metadata.file_type().is_symlink(){
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: {} isn't a symbolic link.", path.display())))
+    }
+
+    return Ok(Data::PathData(fs::read_link(path)?))
+}
+
+
+///Canonicalizes a path. Removes ., .. and resolves links.
+pub fn realpath(path: &Path) -> Result<Data, Error>{
+    return Ok(Data::PathData(path.canonicalize()?))
+}
+
+
+///Lists running processes as pid, parent pid, memory and name. Rows have no header so they can be sorted and filtered.
+pub fn ps() -> Result<Data, Error>{
+    let processes = processes::list_processes()?;
+
+    let rows = processes.into_iter()
+        .map(|x| vec![x.pid.to_string(), x.parent.to_string(), format_size(x.memory), x.name])
+        .collect();
+
+    return Ok(Data::TableData(rows))
+}
+
+
+///Terminates a process by pid, or every process with the given name.
+///Returns the terminated processes.
+pub fn kill(target: &String, force: bool) -> Result<Data, Error>{
+    let running = processes::list_processes()?;
+
+    let targets: Vec<processes::ProcessInfo> = match target.parse::<u32>() {
+        Ok(pid) => running.into_iter().filter(|x| x.pid == pid).collect(),
+        Err(_) => {
+            let name = target.to_lowercase();
+            running.into_iter()
+                .filter(|x| x.pid != std::process::id())
+                .filter(|x| {
+                    let process_name = x.name.to_lowercase();
+                    process_name == name || process_name.trim_end_matches(".exe") == name
+                })
+                .collect()
+        }
+    };
+
+    if targets.is_empty(){
+        return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: No process {} found.", target)))
+    }
+
+    let mut output_string = Vec::<String>::new();
+
+    for process in targets{
+        processes::terminate(process.pid, force)?;
+        output_string.push(format!("Terminated {} {}", process.pid, process.name));
+    }
+
+    return Ok(Data::VecStringData(output_string))
+}
+
+
+///Writes piped data to every given file, one line per entry, and passes it through unchanged.
+pub fn tee(files: &Vec<PathBuf>, data: Data, append: bool) -> Result<Data, Error>{
+    let lines = data.render();
+
+    for file_path in files{
+        let mut file = OpenOptions::new().write(true).create(true).append(append).truncate(!append).open(file_path)?;
+
+        for line in &lines{
+            writeln!(file, "{}", line)?;
+        }
+    }
+
+    return Ok(data)
+}
+
+
+///Returns the first count lines.
+pub fn head(lines: Vec<String>, count: usize) -> Result<Data, Error>{
+    return Ok(Data::VecStringData(lines.into_iter().take(count).collect()))
+}
+
+
+///Returns the last count lines.
+pub fn tail(lines: Vec<String>, count: usize) -> Result<Data, Error>{
+    let skip = lines.len().saturating_sub(count);
+    return Ok(Data::VecStringData(lines.into_iter().skip(skip).collect()))
+}
+
+
+///Prints the last count lines of a file and keeps printing appended lines until Enter is pressed.
+///Lines are logged as they arrive since the command doesn't return while following.
+pub fn tail_follow(path: &Path, count: usize) -> Result<Data, Error>{
+    if path.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot read directory. Use ls instead."))
+    }
+    let mut file = fs::File::open(path)?;
+    let logger = Logger::new();
+    let print = |lines: Vec<String>| Data::VecStringData(lines).render_terminal().iter().for_each(|x| logger.log(x));
+
+    let mut buffer = Vec::<u8>::new();
+    file.read_to_end(&mut buffer)?;
+    let mut position = buffer.len() as u64;
+
+    let lines: Vec<String> = String::from_utf8_lossy(&buffer).lines().map(|x| x.to_string()).collect();
+    let skip = lines.len().saturating_sub(count);
+    print(lines.into_iter().skip(skip).collect());
+    logger.log_err("Following, press Enter to stop.");
+
+    //stdin is read on its own thread so the file can be polled meanwhile
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_reader = Arc::clone(&stop);
+    let reader = thread::spawn(move || {
+        let _ = io::stdin().read_line(&mut String::new());
+        stop_reader.store(true, AtomicOrdering::Relaxed);
+    });
+
+    let mut pending = String::new();
+
+    while !stop.load(AtomicOrdering::Relaxed){
+        thread::sleep(Duration::from_millis(200));
+
+        //a removed file has no more output, like tail -f it is followed until stopped
+        let length = match fs::metadata(path) {
+            Ok(metadata) => metadata.len(),
+            Err(_) => continue
+        };
+
+        //file was truncated, start over
+        if length < position{
+            file.seek(SeekFrom::Start(0))?;
+            position = 0;
+            pending.clear();
+        }
+
+        buffer.clear();
+        file.read_to_end(&mut buffer)?;
+        position += buffer.len() as u64;
+        pending.push_str(&String::from_utf8_lossy(&buffer));
+
+        let mut lines = Vec::<String>::new();
+        while let Some(index) = pending.find('\n'){
+            lines.push(pending[..index].trim_end_matches('\r').to_string());
+            pending.drain(..=index);
+        }
+        print(lines);
+    }
+
+    let _ = reader.join();
+    return Ok(Data::StatusData(0))
+}
+
+
+///Counts lines, words and bytes of every source. Sources are pairs of name and content.
+///A total is added for more than one source.
+pub fn wc(sources: Vec<(String, Vec<u8>)>) -> Result<Data, Error>{
+    let mut output_string = Vec::<String>::new();
+    let mut total: (usize, usize, usize) = (0, 0, 0);
+
+    for (name, content) in &sources{
+        let lines = content.iter().filter(|x| **x == b'\n').count();
+        let words = String::from_utf8_lossy(content).split_whitespace().count();
+
+        output_string.push(format!("{:>8} {:>8} {:>8} {}", lines, words, content.len(), name).trim_end().to_string());
+        total = (total.0 + lines, total.1 + words, total.2 + content.len());
+    }
+
+    if sources.len() > 1{
+        output_string.push(format!("{:>8} {:>8} {:>8} total", total.0, total.1, total.2));
+    }
+
+    return Ok(Data::VecStringData(output_string))
+}
+
+
+///Sorts lines. Key is a whitespace separated field starting at 1.
+///Numeric sort compares the first field, understands sizes like 1.5K and treats non numbers as 0.
+pub fn sort(mut lines: Vec<String>, numeric: bool, reverse: bool, key: Option<usize>) -> Result<Data, Error>{
+    let sort_key = |line: &String| -> String {
+        match key {
+            Some(field) => return line.split_whitespace().nth(field - 1).unwrap_or("").to_string(),
+            None => return line.clone()
+        }
+    };
+
+    lines.sort_by(|a, b| {
+        let ordering = if numeric {
+            parse_number(&sort_key(a)).total_cmp(&parse_number(&sort_key(b)))
+        }
+        else{
+            sort_key(a).cmp(&sort_key(b))
+        };
+
+        if reverse{
+            return ordering.reverse()
+        }
+        return ordering
+    });
+
+    return Ok(Data::VecStringData(lines))
+}
+
+
+fn parse_number(text: &str) -> f64{
+    let field = text.split_whitespace().next().unwrap_or("");
+
+    if let Ok(number) = field.parse::<f64>(){
+        return number
+    }
+
+    //sizes shown by format_size
+    let units = ["B", "K", "M", "G", "T", "P"];
+    for (exponent, unit) in units.iter().enumerate(){
+        if let Some(number) = field.strip_suffix(unit).and_then(|x| x.parse::<f64>().ok()){
+            return number * 1024_f64.powi(exponent as i32)
+        }
+    }
+    return 0.0
+}
+
+
+///Collapses adjacent duplicate lines, optionally prefixed with the number of occurrences.
+pub fn uniq(lines: Vec<String>, count: bool) -> Result<Data, Error>{
+    let mut groups = Vec::<(usize, String)>::new();
+
+    for line in lines{
+        match groups.last_mut() {
+            Some((occurrences, last)) if *last == line => *occurrences += 1,
+            _ => groups.push((1, line))
+        }
+    }
+
+    if count{
+        return Ok(Data::VecStringData(groups.into_iter().map(|(occurrences, line)| format!("{:>7} {}", occurrences, line)).collect()))
+    }
+    return Ok(Data::VecStringData(groups.into_iter().map(|(_, line)| line).collect()))
+}
+
+
+///Applies a sed style s/regex/replacement/flags expression to every line.
+pub fn sed(lines: Vec<String>, expression: &str) -> Result<Data, Error>{
+    return Ok(Data::VecStringData(substitute_lines(lines, expression)?))
+}
+
+
+///Applies a sed style expression to a file in place. The original is kept as file.bak when backup is set.
+pub fn sed_in_place(path: &Path, expression: &str, backup: bool) -> Result<Data, Error>{
+    if path.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot edit directory."))
+    }
+    let content = fs::read_to_string(path)?;
+
+    let line_ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines = substitute_lines(content.lines().map(|x| x.to_string()).collect(), expression)?;
+
+    let mut output = lines.join(line_ending);
+    if content.ends_with('\n'){
+        output.push_str(line_ending);
+    }
+
+    if backup{
+        let mut backup_path = path.as_os_str().to_owned();
+        backup_path.push(".bak");
+        fs::copy(path, backup_path)?;
+    }
+    write_atomic(path, output.as_bytes())?;
+
+    return Ok(Data::PathData(path.to_path_buf()))
+}
+
+
+///Writes to a temporary file next to path and renames it over path, so the file is never left truncated.
+///A link is written through, the file it points to is replaced and the link kept.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), Error>{
+    let resolved = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_symlink() => match path.canonicalize() {
+            Ok(target) => target,
+            //a dangling link creates its target
+            Err(_) => path.parent().unwrap_or(Path::new("")).join(fs::read_link(path)?)
+        },
+        _ => path.to_path_buf()
+    };
+    let path = resolved.as_path();
+
+    let file_name = match path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Invalid path."))
+    };
+    let temporary_path = path.with_file_name(format!(".{}.rcli-tmp", file_name));
+
+    let write_result = (|| {
+        let mut file = OpenOptions::new().write(true).create(true).truncate(true).open(&temporary_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+
+        //keep permissions of the original
+        if let Ok(metadata) = fs::metadata(path){
+            fs::set_permissions(&temporary_path, metadata.permissions())?;
+        }
+        return fs::rename(&temporary_path, path)
+    })();
+
+    if write_result.is_err(){
+        let _ = fs::remove_file(&temporary_path);
+    }
+    return write_result
+}
+
+
+fn substitute_lines(lines: Vec<String>, expression: &str) -> Result<Vec<String>, Error>{
+    let (regex, replacement, global) = parse_substitution(expression)?;
+
+    return Ok(lines.into_iter().map(|line| {
+        if global{
+            return regex.replace_all(&line, replacement.as_str()).to_string()
+        }
+        return regex.replace(&line, replacement.as_str()).to_string()
+    }).collect())
+}
+
+
+///Parses s/regex/replacement/flags. Any character after s is the delimiter and can be escaped with \.
+///Flags are g (every match) and i (case insensitive). Returns the regex, the replacement in regex crate syntax and g.
+fn parse_substitution(expression: &str) -> Result<(Regex, String, bool), Error>{
+    let invalid = || Error::new(ErrorKind::InvalidInput, "Invoker Error: Expected an expression like s/regex/replacement/flags.");
+
+    let mut characters = expression.chars();
+    if characters.next() != Some('s'){
+        return Err(invalid())
+    }
+    let delimiter = characters.next().ok_or_else(invalid)?;
+    if delimiter.is_alphanumeric() || delimiter == '\\' || delimiter.is_whitespace(){
+        return Err(invalid())
+    }
+
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+
+    for character in characters{
+        if escaped{
+            //escaped delimiters are literal, other escapes are kept for the regex and replacement
+            if character != delimiter{
+                parts.last_mut().unwrap().push('\\');
+            }
+            parts.last_mut().unwrap().push(character);
+            escaped = false;
+        }
+        else if character == '\\'{
+            escaped = true;
+        }
+        else if character == delimiter{
+            parts.push(String::new());
+        }
+        else{
+            parts.last_mut().unwrap().push(character);
+        }
+    }
+
+    if parts.len() != 3 || escaped{
+        return Err(invalid())
+    }
+
+    let mut global = false;
+    let mut case_insensitive = false;
+
+    for flag in parts[2].chars(){
+        match flag {
+            'g' => global = true,
+            'i' => case_insensitive = true,
+            _ => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Unknown substitution flag '{}'.", flag)))
+        }
+    }
+
+    let regex = regex::RegexBuilder::new(&parts[0]).case_insensitive(case_insensitive).build();
+    if regex.is_err(){
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Invalid pattern. {}", regex.err().unwrap())))
+    }
+
+    return Ok((regex.unwrap(), sed_replacement(&parts[1]), global))
+}
+
+
+///Converts a sed replacement to regex crate syntax. & is the whole match and \1 to \9 are groups.
+fn sed_replacement(replacement: &str) -> String{
+    let mut output = String::new();
+    let mut characters = replacement.chars();
+
+    while let Some(character) = characters.next(){
+        match character {
+            '$' => output.push_str("$$"),
+            '&' => output.push_str("${0}"),
+            '\\' => {
+                match characters.next() {
+                    Some(digit) if digit.is_ascii_digit() => output.push_str(&format!("${{{}}}", digit)),
+                    Some('n') => output.push('\n'),
+                    Some('t') => output.push('\t'),
+                    Some('$') => output.push_str("$$"),
+                    Some(next) => output.push(next),
+                    None => output.push('\\')
+                }
+            },
+            _ => output.push(character)
+        }
+    }
+
+    return output
+}
+
+
+///Selects fields split by delimiter or character ranges from every line. Ranges are like 1,3-5,7- and start at 1.
+///Lines without the delimiter are kept whole.
+pub fn cut(lines: Vec<String>, delimiter: &str, fields: Option<&String>, characters: Option<&String>) -> Result<Data, Error>{
+    let mut output_string = Vec::<String>::new();
+
+    if let Some(fields) = fields{
+        let ranges = parse_ranges(fields)?;
+
+        for line in lines{
+            if !line.contains(delimiter){
+                output_string.push(line);
+                continue;
+            }
+            let selected: Vec<&str> = line.split(delimiter).enumerate()
+                .filter(|(index, _)| ranges.iter().any(|(start, end)| (*start..=*end).contains(&(index + 1))))
+                .map(|(_, field)| field)
+                .collect();
+
+            output_string.push(selected.join(delimiter));
+        }
+    }
+    else if let Some(characters) = characters{
+        let ranges = parse_ranges(characters)?;
+
+        for line in lines{
+            let selected: String = line.chars().enumerate()
+                .filter(|(index, _)| ranges.iter().any(|(start, end)| (*start..=*end).contains(&(index + 1))))
+                .map(|(_, character)| character)
+                .collect();
+
+            output_string.push(selected);
+        }
+    }
+    else{
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Didn't provide fields or characters."))
+    }
+
+    return Ok(Data::VecStringData(output_string))
+}
+
+
+///Parses ranges like 1,3-5,7- or -2 to inclusive (start, end) pairs.
+fn parse_ranges(ranges: &str) -> Result<Vec<(usize, usize)>, Error>{
+    let invalid = || Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Invalid range '{}'.", ranges));
+    let mut output = Vec::<(usize, usize)>::new();
+
+    for range in ranges.split(','){
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (start, end),
+            None => (range, range)
+        };
+
+        let start = if start.is_empty() { 1 } else { start.trim().parse::<usize>().map_err(|_| invalid())? };
+        let end = if end.is_empty() { usize::MAX } else { end.trim().parse::<usize>().map_err(|_| invalid())? };
+
+        if start == 0 || end < start{
+            return Err(invalid())
+        }
+        output.push((start, end));
+    }
+
+    return Ok(output)
+}
+
+
+///Shows the total size of path and of every directory under it up to max_depth, deepest first.
+///Summary shows only the total. Links aren't followed and unreadable directories count as empty.
+pub fn du(path: &Path, max_depth: Option<usize>, summary: bool, human: bool) -> Result<Data, Error>{
+    let mut rows = Vec::<(u64, PathBuf)>::new();
+    let max_depth = if summary { Some(0) } else { max_depth };
+
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
+    };
+    if metadata.is_dir(){
+        directory_usage(path, 0, max_depth, &mut rows)?;
+    }
+    else{
+        rows.push((metadata.len(), path.to_path_buf()));
+    }
+
+    let size = |bytes: u64| if human { format_size(bytes) } else { bytes.to_string() };
+
+    return Ok(Data::TableData(rows.into_iter().map(|(bytes, path)| vec![size(bytes), path.display().to_string()]).collect()))
+}
+
+
+fn directory_usage(path: &Path, depth: usize, max_depth: Option<usize>, rows: &mut Vec<(u64, PathBuf)>) -> Result<u64, Error>{
+    let mut total: u64 = 0;
+
+    for entry in fs::read_dir(path)?.flatten(){
+        match fs::symlink_metadata(entry.path()) {
+            Ok(meta) if meta.is_dir() => {
+                total += directory_usage(&entry.path(), depth + 1, max_depth, rows).unwrap_or(0);
+            },
+            Ok(meta) => {
+                total += meta.len();
+            },
+            Err(_) => continue
+        }
+    }
+
+    if max_depth.is_none_or(|x| depth <= x){
+        rows.push((total, path.to_path_buf()));
+    }
+    return Ok(total)
+}
+
+
+///Shows size, used and free space of mounted filesystems.
+pub fn df(human: bool) -> Result<Data, Error>{
+    let disks = disks::list_disks()?;
+    let size = |bytes: u64| if human { format_size(bytes) } else { bytes.to_string() };
+
+    let mut rows = vec![vec![String::from("FILESYSTEM"), String::from("SIZE"), String::from("USED"), String::from("FREE"), String::from("USE%"), String::from("MOUNT")]];
+
+    for disk in disks{
+        let used = disk.total.saturating_sub(disk.free);
+        let percentage = if disk.total > 0 { (used as f64 / disk.total as f64 * 100.0).ceil() as u64 } else { 0 };
+
+        rows.push(vec![disk.filesystem, size(disk.total), size(used), size(disk.free), format!("{}%", percentage), disk.mount]);
+    }
+
+    return Ok(Data::TableData(rows))
+}
+
+
+///Builds the directory tree under path up to max_depth. Links are shown with their target and aren't followed.
+pub fn tree(path: &Path, hidden: bool, dirs_first: bool, max_depth: Option<usize>) -> Result<Data, Error>{
+    if !path.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Not a directory."))
+    }
+
+    let mut root = TreeNode { name: path.display().to_string(), path: path.to_path_buf(), is_dir: true, children: vec![] };
+    root.children = tree_children(path, 1, hidden, dirs_first, max_depth);
+
+    return Ok(Data::TreeData(root))
+}
+
+
+fn tree_children(path: &Path, depth: usize, hidden: bool, dirs_first: bool, max_depth: Option<usize>) -> Vec<TreeNode>{
+    if max_depth.is_some_and(|x| depth > x){
+        return vec![]
+    }
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return vec![]
+    };
+    let mut children = Vec::<TreeNode>::new();
+
+    for entry in entries.flatten(){
+        let meta = match fs::symlink_metadata(entry.path()) {
+            Ok(meta) => meta,
+            Err(_) => continue
+        };
+
+        if !hidden && is_hidden(&entry.path(), &meta, false){
+            continue;
+        }
+
+        let mut name = entry.file_name().to_string_lossy().to_string();
+        if meta.file_type().is_symlink(){
+            if let Ok(target) = fs::read_link(entry.path()){
+                name = format!("{} -> {}", name, target.display());
+            }
+        }
+
+        let mut node = TreeNode { name: name, path: entry.path(), is_dir: meta.is_dir(), children: vec![] };
+        if node.is_dir{
+            node.children = tree_children(&entry.path(), depth + 1, hidden, dirs_first, max_depth);
+        }
+        children.push(node);
+    }
+
+    children.sort_by(|a, b| {
+        if dirs_first && a.is_dir != b.is_dir{
+            return b.is_dir.cmp(&a.is_dir)
+        }
+        return a.name.to_lowercase().cmp(&b.name.to_lowercase())
+    });
+
+    return children
+}
+
+
+///Compares two files line by line as a unified diff. Directories are compared by their files,
+///shown as + added, - removed and ~ changed.
+pub fn diff(old: &Path, new: &Path, context: usize, ignore_whitespace: bool) -> Result<Data, Error>{
+    if !old.exists() || !new.exists(){
+        return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
+    }
+    if old.is_dir() && new.is_dir(){
+        return diff_directories(old, new)
+    }
+    if old.is_dir() || new.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Can't compare a file with a directory."))
+    }
+
+    let old_content = fs::read(old)?;
+    let new_content = fs::read(new)?;
+
+    if old_content.contains(&0) || new_content.contains(&0){
+        if old_content == new_content{
+            return Ok(Data::DiffData(vec![]))
+        }
+        return Ok(Data::FailedData(Box::new(Data::DiffData(vec![format!("Binary files {} and {} differ", old.display(), new.display())]))))
+    }
+
+    let old_lines: Vec<String> = String::from_utf8_lossy(&old_content).lines().map(|x| x.to_string()).collect();
+    let new_lines: Vec<String> = String::from_utf8_lossy(&new_content).lines().map(|x| x.to_string()).collect();
+
+    let edits = diff::diff_lines(&old_lines, &new_lines, ignore_whitespace);
+    let mut hunks = diff::unified(&old_lines, &new_lines, &edits, context);
+
+    if hunks.is_empty(){
+        return Ok(Data::DiffData(vec![]))
+    }
+
+    let mut output_string = vec![format!("--- {}", old.display()), format!("+++ {}", new.display())];
+    output_string.append(&mut hunks);
+
+    //differing files fail like diff's exit status 1
+    return Ok(Data::FailedData(Box::new(Data::DiffData(output_string))))
+}
+
+
+fn diff_directories(old: &Path, new: &Path) -> Result<Data, Error>{
+    let mut old_files = BTreeSet::<PathBuf>::new();
+    let mut new_files = BTreeSet::<PathBuf>::new();
+    relative_files(old, Path::new(""), &mut old_files)?;
+    relative_files(new, Path::new(""), &mut new_files)?;
+
+    let mut output_string = Vec::<String>::new();
+
+    for file in old_files.union(&new_files){
+        match (old_files.contains(file), new_files.contains(file)) {
+            (true, false) => output_string.push(format!("- {}", file.display())),
+            (false, true) => output_string.push(format!("+ {}", file.display())),
+            _ => {
+                if diff::first_difference(&old.join(file), &new.join(file))?.is_some(){
+                    output_string.push(format!("~ {}", file.display()));
+                }
+            }
+        }
+    }
+
+    if output_string.is_empty(){
+        return Ok(Data::DiffData(output_string))
+    }
+    return Ok(Data::FailedData(Box::new(Data::DiffData(output_string))))
+}
+
+
+///Collects files under root relative to it. Links aren't followed.
+fn relative_files(root: &Path, relative: &Path, files: &mut BTreeSet<PathBuf>) -> Result<(), Error>{
+    for entry in fs::read_dir(root.join(relative))?.flatten(){
+        let path = relative.join(entry.file_name());
+
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => relative_files(root, &path, files)?,
+            Ok(file_type) if file_type.is_file() => {
+                files.insert(path);
+            },
+            _ => continue
+        }
+    }
+    return Ok(())
+}
+
+
+///Compares two files byte by byte. Shows the first difference, nothing if the files are equal.
+pub fn cmp(first: &Path, second: &Path) -> Result<Data, Error>{
+    if !first.is_file() || !second.is_file(){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: cmp compares two files."))
+    }
+
+    match diff::first_difference(first, second)? {
+        None => return Ok(Data::StatusData(0)),
+        Some((offset, line)) => {
+            let first_length = fs::metadata(first)?.len();
+            let second_length = fs::metadata(second)?.len();
+
+            //one file is a prefix of the other
+            if offset > first_length.min(second_length){
+                let shorter = if first_length < second_length { first } else { second };
+                return Ok(Data::FailedData(Box::new(Data::VecStringData(vec![format!("EOF on {} after byte {}", shorter.display(), offset - 1)]))))
+            }
+            return Ok(Data::FailedData(Box::new(Data::VecStringData(vec![format!("{} {} differ: byte {}, line {}", first.display(), second.display(), offset, line)]))))
+        }
+    }
+}
+
+
+///Packs sources into an archive, skipping names matching the exclude globs. Returns the archive path.
+pub fn archive(sources: &[PathBuf], archive_path: &Path, exclude: &[String]) -> Result<Data, Error>{
+    let exclude = exclude.iter().map(|x| glob_to_regex(x)).collect::<Result<Vec<Regex>, Error>>()?;
+
+    archives::create(archive_path, sources, &exclude)?;
+    return Ok(Data::PathData(archive_path.to_path_buf()))
+}
+
+
+///Extracts an archive into destination and returns the destination path, or only lists its entries.
+pub fn extract(archive_path: &Path, destination: &Path, list_only: bool) -> Result<Data, Error>{
+    if !archive_path.is_file(){
+        return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
+    }
+    if list_only{
+        return Ok(Data::VecStringData(archives::list(archive_path)?))
+    }
+
+    archives::extract(archive_path, destination)?;
+    return Ok(Data::PathData(destination.to_path_buf()))
+}
+
+
+///Digests of files, or of the piped content when there are no files. Lines are "digest  path", piped content is shown as -.
+pub fn hash(paths: &[PathBuf], piped_content: Option<Vec<u8>>, algorithm: HashAlgorithm) -> Result<Data, Error>{
+    let mut output = Vec::<String>::new();
+
+    if let Some(content) = piped_content{
+        output.push(format!("{}  -", hashes::hash_reader(content.as_slice(), algorithm)?));
+    }
+    for path in paths{
+        if !path.exists(){
+            return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: Invalid path {}.", path.display())))
+        }
+        output.push(format!("{}  {}", hashes::hash_file(path, algorithm)?, path.display()));
+    }
+
+    return Ok(Data::VecStringData(output))
+}
+
+
+///Verifies the files of a checksum list and reports OK or FAILED for each.
+///Without an algorithm it is taken from the digest length. Any failure returns an error holding the whole report.
+pub fn hash_check(list: &Path, algorithm: Option<HashAlgorithm>) -> Result<Data, Error>{
+    if !list.is_file(){
+        return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Invalid path."))
+    }
+
+    let mut output = Vec::<String>::new();
+    let mut failed: usize = 0;
+    let mut total: usize = 0;
+
+    for line in io::BufReader::new(fs::File::open(list)?).lines(){
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with('#'){
+            continue;
+        }
+
+        let (expected, path) = match hashes::parse_checksum_line(&line) {
+            Some(entry) => entry,
+            None => {
+                output.push(format!("Improperly formatted line: {}", line));
+                failed += 1;
+                continue;
+            }
+        };
+        total += 1;
+
+        let line_algorithm = match algorithm.or(HashAlgorithm::from_digest(&expected)) {
+            Some(line_algorithm) => line_algorithm,
+            None => {
+                output.push(format!("{}: FAILED unknown digest length", path));
+                failed += 1;
+                continue;
+            }
+        };
+
+        match hashes::hash_file(Path::new(&path), line_algorithm) {
+            Ok(digest) if digest == expected => output.push(format!("{}: OK", path)),
+            Ok(_) => {
+                output.push(format!("{}: FAILED", path));
+                failed += 1;
+            },
+            Err(_) => {
+                output.push(format!("{}: FAILED open or read", path));
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0{
+        output.push(format!("Invoker Error: {} of {} checksums failed.", failed, total));
+        return Err(Error::new(ErrorKind::InvalidData, output.join("\n")))
+    }
+    return Ok(Data::VecStringData(output))
+}
+
+
+///Lists all commands with their invocations, or shows the manual of a given command.
+pub fn help(command: Option<&String>, terminal_instance: &mut Terminal) -> Result<Data, Error>{
+    let grammar = terminal_instance.get_instance_grammar();
+    let mut output_string = Vec::<String>::new();
+
+    match command {
+        Some(name) => {
+            let manual = grammar.get_commands().iter().find(|(command_type, invocation)| {
+                command_type.ne(&&CommandType::INVALID) && invocation.match_string(name).is_some()
+            });
+
+            if manual.is_none(){
+                return Err(lexical_analyzer::unknown_command(name, &grammar))
+            }
+            let manual = manual.unwrap().1;
+
+            output_string.push(format!("{} - {}", manual.get_invocation_names().join(", "), manual.get_summary()));
+            output_string.push(String::new());
+            output_string.push(format!("Usage: {}", manual.get_usage()));
+
+            if !manual.get_flags().is_empty(){
+                output_string.push(String::new());
+                output_string.push(String::from("Flags:"));
+
+                for flag in manual.get_flags(){
+                    let description = match manual.get_flag_description(&flag) {
+                        Some(description) => description.clone(),
+                        None => String::new()
+                    };
+                    output_string.push(format!("  {:<24}{}", grammar.get_flag_values(&flag).join(", "), description));
+                }
+            }
+
+            if !manual.get_examples().is_empty(){
+                output_string.push(String::new());
+                output_string.push(String::from("Examples:"));
+                manual.get_examples().iter().for_each(|x| output_string.push(format!("  {}", x)));
+            }
+        },
+        None => {
+            let mut commands: Vec<(String, String)> = grammar.get_commands().iter()
+                .filter(|(command_type, _)| command_type.ne(&&CommandType::INVALID))
+                .map(|(_, invocation)| (invocation.get_invocation_names().join(", "), invocation.get_summary().clone()))
+                .collect();
+            commands.sort();
+
+            for (names, summary) in commands{
+                output_string.push(format!("{:<20}{}", names, summary));
+            }
+            output_string.push(String::new());
+            output_string.push(String::from("Use help <command> to show its usage and flags."));
+        }
+    }
+
+    return Ok(Data::VecStringData(output_string))
+}
+
+
+///Exits RCli
+pub fn exit() -> Result<Data, io::Error> {
+    Ok(Data::StatusData(1))
+}
+
+///Processes invalid commands
+pub fn invalid() -> Result<Data, io::Error> {
+    Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Invalid command."))
+}
+
+
+/*
+    HELPER FUNCTIONS
+*/
+
+///Helper function that asks a yes or no question on the terminal.
+pub fn confirm(question: &str) -> Result<bool, Error>{
+    print!("{} (y/n) ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    return Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+
+///Helper function that counts the entries under path (path included) and their size in bytes. Links aren't followed.
+///Counting stops once there are more entries than limit.
+fn tree_stats(path: &Path, limit: Option<usize>) -> (usize, u64){
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return (0, 0)
+    };
+    if !metadata.is_dir(){
+        return (1, metadata.len())
+    }
+
+    let mut stats = (1, 0);
+    for entry in fs::read_dir(path).into_iter().flatten().flatten(){
+        if limit.is_some_and(|x| stats.0 > x){
+            break;
+        }
+
+        let (entries, bytes) = tree_stats(&entry.path(), limit.map(|x| x - stats.0));
+        stats = (stats.0 + entries, stats.1 + bytes);
+    }
+    return stats
+}
+
+
+///Helper function that formats seconds since the epoch as a UTC YYYY-MM-DD HH:MM:SS time. Inverse of parse_time.
+pub fn format_time(seconds: u64) -> String{
+    let (days, rest) = (seconds / 86400, seconds % 86400);
+
+    //civil date of a day count, with years starting in march
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+
+    return format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, rest / 3600, rest % 3600 / 60, rest % 60)
+}
+
+
+///Helper function that formats a byte count as a human readable size (K, M, G...).
+pub fn format_size(bytes: u64) -> String{
+    let units = ["B", "K", "M", "G", "T", "P"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < units.len() - 1{
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0{
+        return format!("{}{}", bytes, units[unit])
+    }
+    return format!("{:.1}{}", size, units[unit])
+}
+
+
+///Helper function that displays a link as `link -> target` without following it.
+fn display_link(link_path: &Path) -> PathBuf{
+    //canonicalize the parent only, the link itself would be resolved
+    let parent = match link_path.parent().map(|x| x.canonicalize()) {
+        Some(Ok(parent)) => parent.join(link_path.file_name().unwrap()),
+        _ => link_path.to_path_buf()
+    };
+
+    match fs::read_link(link_path) {
+        Ok(target) => return PathBuf::from(format!("{} -> {}", parent.display(), target.display())),
+        Err(_) => return parent
+    }
+}
+
+///Helper function that checks the hidden attribute of an entry. System entries count as hidden when system is set.
+#[cfg(windows)]
+pub fn is_hidden(_path: &Path, metadata: &fs::Metadata, system: bool) -> bool{
+    let attributes = windows_file_attributes::match_attributes(metadata.file_attributes());
+
+    return attributes.contains(&windows_file_attributes::WindowsAttributes::HIDDEN) || (system && attributes.contains(&windows_file_attributes::WindowsAttributes::FILE_SYSTEM))
+}
+
+///Helper function that checks if an entry is hidden. Names starting with a dot are hidden outside of windows.
+#[cfg(not(windows))]
+pub fn is_hidden(path: &Path, _metadata: &fs::Metadata, _system: bool) -> bool{
+    return path.file_name().is_some_and(|x| x.to_string_lossy().starts_with('.'))
+}
+
+///Helper function that converts a glob (*, ? and [...]) to an anchored regex.
+///Used for wildcard expansion and name matching.
+pub fn glob_to_regex(glob: &str) -> Result<Regex, Error>{
+    let mut pattern = String::from("^");
+    let mut in_class = false;
+
+    for character in glob.chars(){
+        match character{
+            '*' if !in_class => pattern.push_str(".*"),
+            '?' if !in_class => pattern.push('.'),
+            '[' if !in_class => {
+                in_class = true;
+                pattern.push('[');
+            },
+            '!' if pattern.ends_with('[') && in_class => pattern.push('^'),
+            ']' if in_class => {
+                in_class = false;
+                pattern.push(']');
+            },
+            _ => pattern.push_str(&regex::escape(&character.to_string()))
+        }
+    }
+    pattern.push('$');
+
+    match Regex::new(&pattern) {
+        Ok(regex) => return Ok(regex),
+        Err(error) => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Invalid pattern. {}", error)))
+    }
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_copy_onto_itself(){
+        let directory = std::env::temp_dir().join(format!("rcli-copy-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let file = directory.join("a.txt");
+        fs::write(&file, "content").unwrap();
+        fs::hard_link(&file, directory.join("b.txt")).unwrap();
+
+        let options = CopyOptions { overwrite: Overwrite::FORCE, preserve: false, dereference: false, progress: false };
+        assert!(copy(&file, &file, &options).is_err());
+        assert!(copy(&file, &directory, &options).is_err());
+        #[cfg(unix)]
+        assert!(copy(&file, &directory.join("b.txt"), &options).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "content");
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_parse_time(){
+        assert_eq!(parse_time("@86400").unwrap(), UNIX_EPOCH + Duration::from_secs(86400));
+        assert_eq!(parse_time("2024-02-29").unwrap(), UNIX_EPOCH + Duration::from_secs(1709164800));
+        assert_eq!(parse_time("2024-03-01T12:30:15").unwrap(), UNIX_EPOCH + Duration::from_secs(1709296215));
+        assert_eq!(parse_time("2000-01-01 00:00").unwrap(), UNIX_EPOCH + Duration::from_secs(946684800));
+        assert!(parse_time("2024-13-01").is_err());
+        assert!(parse_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_format_time(){
+        assert_eq!(format_time(0), "1970-01-01 00:00:00");
+        assert_eq!(format_time(1709296215), "2024-03-01 12:30:15");
+        assert_eq!(format_time(951782400), "2000-02-29 00:00:00");
+    }
+
+    #[test]
+    fn test_expand_tilde(){
+        let home = Path::new("/home/user");
+        assert_eq!(expand_tilde("~", home), "/home/user");
+        assert_eq!(expand_tilde("~/src/main.rs", home), Path::new("/home/user").join("src/main.rs").display().to_string());
+        assert_eq!(expand_tilde("~user", home), "~user");
+        assert_eq!(expand_tilde("a/~", home), "a/~");
+    }
+
+    #[test]
+    fn test_parse_substitution(){
+        let (regex, replacement, global) = parse_substitution(r"s/port=(\d+)/port=\1 # &/g").unwrap();
+        assert_eq!(regex.as_str(), r"port=(\d+)");
+        assert_eq!(replacement, "port=${1} # ${0}");
+        assert!(global);
+
+        let (regex, replacement, global) = parse_substitution(r"s|a\|b|$x|i").unwrap();
+        assert!(regex.is_match("A|B"));
+        assert_eq!(replacement, "$$x");
+        assert!(!global);
+
+        assert!(parse_substitution("s/a/b").is_err());
+        assert!(parse_substitution("s/(/b/").is_err());
+        assert!(parse_substitution("s/a/b/x").is_err());
+    }
+
+    #[test]
+    fn test_sed(){
+        let lines = vec!["a=1 a=2".to_string()];
+
+        assert_eq!(sed(lines.clone(), r"s/a=(\d)/b=\1/").unwrap().render(), vec!["b=1 a=2"]);
+        assert_eq!(sed(lines, r"s/a=(\d)/b=\1/g").unwrap().render(), vec!["b=1 b=2"]);
+    }
+
+    #[test]
+    fn test_cut(){
+        let lines = vec!["a,b,c,d".to_string(), "none".to_string()];
+
+        assert_eq!(cut(lines.clone(), ",", Some(&"1,3-".to_string()), None).unwrap().render(), vec!["a,c,d", "none"]);
+        assert_eq!(cut(lines, ",", None, Some(&"-3".to_string())).unwrap().render(), vec!["a,b", "non"]);
+
+        assert!(parse_ranges("0-2").is_err());
+        assert!(parse_ranges("3-1").is_err());
+        assert!(parse_ranges("x").is_err());
+    }
+}
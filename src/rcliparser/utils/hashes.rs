@@ -0,0 +1,139 @@
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read};
+use std::path::Path;
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+
+///Supported hash algorithms.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum HashAlgorithm{
+    SHA256,
+    SHA1,
+    MD5,
+    BLAKE3
+}
+
+impl HashAlgorithm{
+    pub fn from_name(name: &str) -> Result<Self, Error>{
+        match name.to_lowercase().replace('-', "").as_str() {
+            "sha256" => return Ok(HashAlgorithm::SHA256),
+            "sha1" => return Ok(HashAlgorithm::SHA1),
+            "md5" => return Ok(HashAlgorithm::MD5),
+            "blake3" => return Ok(HashAlgorithm::BLAKE3),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Unknown hash algorithm {}. Use sha256, sha1, md5 or blake3.", name)))
+        }
+    }
+
+    ///Algorithm of a hex digest, by its length. Blake3 and sha256 digests have the same length, sha256 is assumed.
+    pub fn from_digest(digest: &str) -> Option<Self>{
+        match digest.len() {
+            64 => return Some(HashAlgorithm::SHA256),
+            40 => return Some(HashAlgorithm::SHA1),
+            32 => return Some(HashAlgorithm::MD5),
+            _ => return None
+        }
+    }
+}
+
+
+enum Hasher{
+    SHA256(Sha256),
+    SHA1(Sha1),
+    MD5(Md5),
+    BLAKE3(Box<blake3::Hasher>)
+}
+
+impl Hasher{
+    fn new(algorithm: HashAlgorithm) -> Self{
+        match algorithm {
+            HashAlgorithm::SHA256 => return Hasher::SHA256(Sha256::new()),
+            HashAlgorithm::SHA1 => return Hasher::SHA1(Sha1::new()),
+            HashAlgorithm::MD5 => return Hasher::MD5(Md5::new()),
+            HashAlgorithm::BLAKE3 => return Hasher::BLAKE3(Box::new(blake3::Hasher::new()))
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]){
+        match self {
+            Hasher::SHA256(hasher) => hasher.update(bytes),
+            Hasher::SHA1(hasher) => hasher.update(bytes),
+            Hasher::MD5(hasher) => hasher.update(bytes),
+            Hasher::BLAKE3(hasher) => { hasher.update(bytes); }
+        }
+    }
+
+    fn finalize(self) -> Vec<u8>{
+        match self {
+            Hasher::SHA256(hasher) => return hasher.finalize().to_vec(),
+            Hasher::SHA1(hasher) => return hasher.finalize().to_vec(),
+            Hasher::MD5(hasher) => return hasher.finalize().to_vec(),
+            Hasher::BLAKE3(hasher) => return hasher.finalize().as_bytes().to_vec()
+        }
+    }
+}
+
+
+///Hex digest of everything read from reader. Reads in blocks so large files aren't loaded into memory.
+pub fn hash_reader<R: Read>(mut reader: R, algorithm: HashAlgorithm) -> Result<String, Error>{
+    let mut hasher = Hasher::new(algorithm);
+    let mut buffer = vec![0_u8; 64 * 1024];
+
+    loop{
+        let read = reader.read(&mut buffer)?;
+        if read == 0{
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    return Ok(hasher.finalize().iter().map(|x| format!("{:02x}", x)).collect())
+}
+
+
+///Hex digest of a file.
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String, Error>{
+    if path.is_dir(){
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: {} is a directory.", path.display())))
+    }
+    return hash_reader(File::open(path)?, algorithm)
+}
+
+
+///Splits a checksum list line into digest and path. Accepts "digest  path" and the binary marker "digest *path".
+pub fn parse_checksum_line(line: &str) -> Option<(String, String)>{
+    let (digest, path) = line.trim_end().split_once(' ')?;
+
+    let path = match path.strip_prefix(' ').or(path.strip_prefix('*')) {
+        Some(path) => path,
+        None => path
+    };
+    if digest.is_empty() || path.is_empty() || !digest.chars().all(|x| x.is_ascii_hexdigit()){
+        return None
+    }
+    return Some((digest.to_lowercase(), path.to_string()))
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_hash_reader(){
+        assert_eq!(hash_reader("abc".as_bytes(), HashAlgorithm::SHA256).unwrap(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(hash_reader("abc".as_bytes(), HashAlgorithm::SHA1).unwrap(), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(hash_reader("abc".as_bytes(), HashAlgorithm::MD5).unwrap(), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hash_reader("".as_bytes(), HashAlgorithm::BLAKE3).unwrap(), "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262");
+    }
+
+    #[test]
+    fn test_parse_checksum_line(){
+        assert_eq!(parse_checksum_line("900150983CD24FB0D6963F7D28E17F72  build/app.exe"), Some(("900150983cd24fb0d6963f7d28e17f72".to_string(), "build/app.exe".to_string())));
+        assert_eq!(parse_checksum_line("abcd *my file.bin"), Some(("abcd".to_string(), "my file.bin".to_string())));
+        assert_eq!(parse_checksum_line("not a checksum"), None);
+        assert_eq!(parse_checksum_line("abcd"), None);
+    }
+}
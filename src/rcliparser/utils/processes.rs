@@ -0,0 +1,138 @@
+use std::io::{Error, ErrorKind};
+use std::process::{Command, Output};
+
+#[cfg(target_os = "linux")]
+use std::fs;
+
+
+///Running process as reported by the operating system.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ProcessInfo{
+    pub pid: u32,
+    pub parent: u32,
+    pub name: String,
+    ///Resident memory in bytes.
+    pub memory: u64
+}
+
+
+///Lists running processes by reading /proc.
+#[cfg(target_os = "linux")]
+pub fn list_processes() -> Result<Vec<ProcessInfo>, Error>{
+    let mut processes = Vec::<ProcessInfo>::new();
+
+    for entry in fs::read_dir("/proc")?.flatten(){
+        let pid = match entry.file_name().to_string_lossy().parse::<u32>() {
+            Ok(pid) => pid,
+            Err(_) => continue
+        };
+
+        //processes can exit while reading
+        let stat = match fs::read_to_string(entry.path().join("stat")) {
+            Ok(stat) => stat,
+            Err(_) => continue
+        };
+
+        //name is in parentheses and can contain spaces
+        let (name_start, name_end) = match (stat.find('('), stat.rfind(')')) {
+            (Some(start), Some(end)) => (start, end),
+            _ => continue
+        };
+        let name = stat[name_start + 1..name_end].to_string();
+        let parent = stat[name_end + 1..].split_whitespace().nth(1).and_then(|x| x.parse::<u32>().ok()).unwrap_or(0);
+
+        let memory = fs::read_to_string(entry.path().join("status")).ok()
+            .and_then(|status| {
+                status.lines()
+                    .find(|line| line.starts_with("VmRSS:"))
+                    .and_then(|line| line.split_whitespace().nth(1).and_then(|x| x.parse::<u64>().ok()))
+            })
+            .unwrap_or(0) * 1024;
+
+        processes.push(ProcessInfo { pid: pid, parent: parent, name: name, memory: memory });
+    }
+
+    processes.sort_by_key(|x| x.pid);
+    return Ok(processes)
+}
+
+
+///Lists running processes through CIM, since std has no process enumeration on windows.
+#[cfg(windows)]
+pub fn list_processes() -> Result<Vec<ProcessInfo>, Error>{
+    let query = "Get-CimInstance Win32_Process | ForEach-Object { \"$($_.ProcessId),$($_.ParentProcessId),$($_.WorkingSetSize),$($_.Name)\" }";
+    let output = run(Command::new("powershell").args(["-NoProfile", "-Command", query]))?;
+
+    if !output.status.success(){
+        return Err(Error::other("Invoker Error: Couldn't list processes."))
+    }
+
+    let mut processes = Vec::<ProcessInfo>::new();
+
+    for line in String::from_utf8_lossy(&output.stdout).lines(){
+        //name is last since it can contain commas
+        let parts: Vec<&str> = line.trim().splitn(4, ',').collect();
+        if parts.len() != 4{
+            continue;
+        }
+
+        let pid = match parts[0].parse::<u32>() {
+            Ok(pid) => pid,
+            Err(_) => continue
+        };
+
+        processes.push(ProcessInfo {
+            pid: pid,
+            parent: parts[1].parse::<u32>().unwrap_or(0),
+            name: parts[3].to_string(),
+            memory: parts[2].parse::<u64>().unwrap_or(0)
+        });
+    }
+
+    processes.sort_by_key(|x| x.pid);
+    return Ok(processes)
+}
+
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn list_processes() -> Result<Vec<ProcessInfo>, Error>{
+    return Err(Error::new(ErrorKind::Unsupported, "Invoker Error: Listing processes isn't supported on this platform."))
+}
+
+
+///Terminates a process. Force kills without letting the process clean up.
+pub fn terminate(pid: u32, force: bool) -> Result<(), Error>{
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("taskkill");
+        command.args(["/PID", &pid.to_string()]);
+        if force{
+            command.arg("/F");
+        }
+        command
+    }
+    else{
+        let mut command = Command::new("kill");
+        command.args([if force { "-KILL" } else { "-TERM" }, &pid.to_string()]);
+        command
+    };
+
+    let output = run(&mut command)?;
+
+    if !output.status.success(){
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(Error::new(ErrorKind::PermissionDenied, format!("Invoker Error: Couldn't terminate process {}. {}", pid, message)))
+    }
+    return Ok(())
+}
+
+
+///Runs an external command and collects its output. A missing binary is reported by name instead of as a bare os error.
+pub fn run(command: &mut Command) -> Result<Output, Error>{
+    match command.output() {
+        Ok(output) => return Ok(output),
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: Couldn't run {}, it wasn't found.", command.get_program().to_string_lossy())))
+        },
+        Err(error) => return Err(error)
+    }
+}
@@ -0,0 +1,531 @@
+use std::cmp;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use regex::{Regex, RegexBuilder};
+
+use super::functions::{glob_to_regex, is_hidden};
+
+
+///Options of a content search (grep).
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct SearchOptions{
+    pub ignore_case: bool,
+    pub invert: bool,
+    pub fixed: bool,
+    pub word: bool,
+    ///Context lines before and after every selected line.
+    pub before: usize,
+    pub after: usize,
+    ///File name globs a file must match to be searched in directories.
+    pub include: Vec<String>,
+    ///File and directory name globs that are skipped in directories.
+    pub exclude: Vec<String>,
+    pub gitignore: bool
+}
+
+///Line of a search result. Context lines aren't matches.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct LineMatch{
+    pub line_number: usize,
+    pub line: String,
+    pub is_match: bool
+}
+
+
+///Compiles the pattern as given. Fixed patterns are escaped and word patterns must match whole words.
+pub fn build_regex(pattern: &str, options: &SearchOptions) -> Result<Regex, Error>{
+    let mut pattern = match options.fixed {
+        true => regex::escape(pattern),
+        false => pattern.to_string()
+    };
+    if options.word{
+        pattern = format!(r"\b(?:{})\b", pattern);
+    }
+
+    match RegexBuilder::new(&pattern).case_insensitive(options.ignore_case).build() {
+        Ok(regex) => return Ok(regex),
+        Err(error) => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Invalid pattern. {}", error)))
+    }
+}
+
+
+///Returns selected lines (matching, or not matching when inverted) and their context lines in order.
+pub fn search_lines(lines: &[String], regex: &Regex, options: &SearchOptions) -> Vec<LineMatch>{
+    let selected: Vec<bool> = lines.iter().map(|line| regex.is_match(line) != options.invert).collect();
+
+    let mut output = Vec::<LineMatch>::new();
+    //index after the last pushed line, so context isn't repeated
+    let mut next_index: usize = 0;
+
+    for (index, is_match) in selected.iter().enumerate(){
+        if !is_match{
+            continue;
+        }
+
+        let start = index.saturating_sub(options.before).max(next_index);
+        let end = (index + options.after).min(lines.len() - 1);
+
+        for context_index in start..=end{
+            //a later match inside the after context is pushed by its own iteration
+            if context_index > index && selected[context_index]{
+                break;
+            }
+            output.push(LineMatch {
+                line_number: context_index + 1,
+                line: lines[context_index].clone(),
+                is_match: selected[context_index]
+            });
+            next_index = context_index + 1;
+        }
+    }
+
+    return output
+}
+
+
+///Lists files to search under a directory. Links to directories aren't followed and .git is always skipped.
+pub fn collect_files(root: &Path, options: &SearchOptions) -> Result<Vec<PathBuf>, Error>{
+    let include = options.include.iter().map(|x| glob_to_regex(x)).collect::<Result<Vec<Regex>, Error>>()?;
+    let exclude = options.exclude.iter().map(|x| glob_to_regex(x)).collect::<Result<Vec<Regex>, Error>>()?;
+
+    let mut files = Vec::<PathBuf>::new();
+    let mut ignore_rules = Vec::<IgnoreRules>::new();
+
+    walk(root, &include, &exclude, options.gitignore, &mut ignore_rules, &mut files)?;
+
+    return Ok(files)
+}
+
+
+fn walk(directory: &Path, include: &Vec<Regex>, exclude: &Vec<Regex>, gitignore: bool, ignore_rules: &mut Vec<IgnoreRules>, files: &mut Vec<PathBuf>) -> Result<(), Error>{
+    let rules_added = gitignore && match IgnoreRules::read(directory) {
+        Some(rules) => {
+            ignore_rules.push(rules);
+            true
+        },
+        None => false
+    };
+
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(directory)?.flatten().collect();
+    entries.sort_by_key(|x| x.file_name());
+
+    for entry in entries{
+        let name = entry.file_name().to_string_lossy().to_string();
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(_) => continue
+        };
+        let is_dir = file_type.is_dir();
+
+        if name.eq(".git") || exclude.iter().any(|x| x.is_match(&name)){
+            continue;
+        }
+        if ignore_rules.iter().rev().find_map(|x| x.is_ignored(&entry.path(), is_dir)).unwrap_or(false){
+            continue;
+        }
+
+        if is_dir{
+            //a subdirectory that can't be read doesn't stop the search
+            let _ = walk(&entry.path(), include, exclude, gitignore, ignore_rules, files);
+        }
+        else if (include.is_empty() || include.iter().any(|x| x.is_match(&name))) && fs::metadata(entry.path()).is_ok_and(|x| x.is_file()){
+            files.push(entry.path());
+        }
+    }
+
+    if rules_added{
+        ignore_rules.pop();
+    }
+    return Ok(())
+}
+
+
+///Searches files on all available threads. Binary and unreadable files are skipped.
+///Results keep the order of files and only contain files with selected lines.
+pub fn search_files(files: &[PathBuf], regex: &Regex, options: &SearchOptions) -> Vec<(PathBuf, Vec<LineMatch>)>{
+    let threads = thread::available_parallelism().map(|x| x.get()).unwrap_or(1).min(files.len()).max(1);
+    let next_file = AtomicUsize::new(0);
+
+    let mut results = Vec::<(usize, Vec<LineMatch>)>::new();
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads).map(|_| scope.spawn(|| {
+            let mut found = Vec::<(usize, Vec<LineMatch>)>::new();
+
+            loop{
+                let index = next_file.fetch_add(1, Ordering::Relaxed);
+                if index >= files.len(){
+                    break;
+                }
+
+                if let Some(lines) = read_text(&files[index]){
+                    let matches = search_lines(&lines, regex, options);
+                    if matches.iter().any(|x| x.is_match){
+                        found.push((index, matches));
+                    }
+                }
+            }
+            return found
+        })).collect();
+
+        for worker in workers{
+            results.append(&mut worker.join().unwrap_or_default());
+        }
+    });
+
+    results.sort_by_key(|x| x.0);
+    return results.into_iter().map(|(index, matches)| (files[index].clone(), matches)).collect()
+}
+
+
+///Reads a file as lines. Returns None for binary files, which contain a zero byte near the start.
+fn read_text(path: &Path) -> Option<Vec<String>>{
+    let content = fs::read(path).ok()?;
+
+    if content.iter().take(8000).any(|x| *x == 0){
+        return None
+    }
+    return Some(String::from_utf8_lossy(&content).lines().map(|x| x.to_string()).collect())
+}
+
+
+///Rules of a .gitignore file. Patterns match paths relative to the directory of the file.
+struct IgnoreRules{
+    base: PathBuf,
+    ///Pattern, negated (!) and directory only (trailing /).
+    rules: Vec<(Regex, bool, bool)>
+}
+
+impl IgnoreRules{
+    fn read(directory: &Path) -> Option<Self>{
+        let content = fs::read_to_string(directory.join(".gitignore")).ok()?;
+        let rules: Vec<(Regex, bool, bool)> = content.lines().filter_map(ignore_rule).collect();
+
+        if rules.is_empty(){
+            return None
+        }
+        return Some(IgnoreRules { base: directory.to_path_buf(), rules: rules })
+    }
+
+    ///Returns whether the last matching rule ignores the path, or None if no rule matches.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> Option<bool>{
+        let relative = path.strip_prefix(&self.base).ok()?;
+        let relative: Vec<String> = relative.components().map(|x| x.as_os_str().to_string_lossy().to_string()).collect();
+        let relative = relative.join("/");
+
+        return self.rules.iter().rev()
+            .find(|(regex, _, dir_only)| (is_dir || !dir_only) && regex.is_match(&relative))
+            .map(|(_, negated, _)| !negated)
+    }
+}
+
+
+///Converts a .gitignore line to a regex. Patterns without a slash match at any depth.
+fn ignore_rule(line: &str) -> Option<(Regex, bool, bool)>{
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#'){
+        return None
+    }
+
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line.strip_prefix('\\').unwrap_or(line))
+    };
+    let (dir_only, line) = match line.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, line)
+    };
+    let anchored = line.contains('/');
+    let line = line.trim_start_matches('/');
+
+    let mut pattern = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut characters = line.chars().peekable();
+
+    while let Some(character) = characters.next(){
+        match character {
+            '*' if characters.peek() == Some(&'*') => {
+                characters.next();
+                //**/ matches any number of directories
+                if characters.peek() == Some(&'/'){
+                    characters.next();
+                    pattern.push_str("(?:.*/)?");
+                }
+                else{
+                    pattern.push_str(".*");
+                }
+            },
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            '[' => pattern.push('['),
+            ']' => pattern.push(']'),
+            '!' if pattern.ends_with('[') => pattern.push('^'),
+            _ => pattern.push_str(&regex::escape(&character.to_string()))
+        }
+    }
+    pattern.push('$');
+
+    return Regex::new(&pattern).ok().map(|x| (x, negated, dir_only))
+}
+
+
+///Entry type predicate of find.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum EntryType{
+    FILE,
+    DIRECTORY,
+    LINK
+}
+
+///Predicates of find. An entry is a result when it matches all given predicates.
+#[derive(Debug, Clone)]
+pub struct FindOptions{
+    pub name: Regex,
+    pub entry_type: Option<EntryType>,
+    ///Ordering, amount and unit in bytes. Equal compares the size rounded up to the unit.
+    pub size: Option<(cmp::Ordering, u64, u64)>,
+    ///Ordering, amount and unit of the time since the last modification. Equal compares whole units.
+    pub modified: Option<(cmp::Ordering, u64, Duration)>,
+    pub max_depth: Option<usize>,
+    ///Includes hidden and system entries.
+    pub hidden: bool
+}
+
+
+///Compiles a find name pattern. Globs and regexes must match the full name.
+pub fn name_pattern(pattern: &str, is_regex: bool, ignore_case: bool) -> Result<Regex, Error>{
+    let pattern = match is_regex {
+        true => format!("^(?:{})$", pattern),
+        false => glob_to_regex(pattern)?.as_str().to_string()
+    };
+
+    match RegexBuilder::new(&pattern).case_insensitive(ignore_case).build() {
+        Ok(regex) => return Ok(regex),
+        Err(error) => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Invalid pattern. {}", error)))
+    }
+}
+
+
+///Parses f, d or l (file, dir, link).
+pub fn parse_entry_type(entry_type: &str) -> Result<EntryType, Error>{
+    match entry_type {
+        "f" | "file" => return Ok(EntryType::FILE),
+        "d" | "dir" | "directory" => return Ok(EntryType::DIRECTORY),
+        "l" | "link" => return Ok(EntryType::LINK),
+        _ => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Unknown type '{}'. Use f, d or l.", entry_type)))
+    }
+}
+
+
+///Parses sizes like +10M (more than), -4K (less than) or 100 (bytes). Units are K, M and G.
+pub fn parse_size(size: &str) -> Result<(cmp::Ordering, u64, u64), Error>{
+    let (ordering, size) = split_ordering(size);
+
+    let (amount, unit) = match size.char_indices().last() {
+        Some((index, 'K' | 'k')) => (&size[..index], 1024),
+        Some((index, 'M' | 'm')) => (&size[..index], 1024 * 1024),
+        Some((index, 'G' | 'g')) => (&size[..index], 1024 * 1024 * 1024),
+        _ => (size, 1)
+    };
+
+    match amount.parse::<u64>() {
+        //bytes are compared as amount * unit
+        Ok(amount) if amount.checked_mul(unit).is_none() => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Size '{}' is too large.", size))),
+        Ok(amount) => return Ok((ordering, amount, unit)),
+        Err(_) => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Invalid size '{}'.", size)))
+    }
+}
+
+
+///Parses ages like +7 (older than 7 days), -2h (newer than 2 hours) or 30m. Units are m, h and d (default).
+pub fn parse_age(age: &str) -> Result<(cmp::Ordering, u64, Duration), Error>{
+    let (ordering, age) = split_ordering(age);
+
+    let (amount, unit) = match age.char_indices().last() {
+        Some((index, 'm')) => (&age[..index], Duration::from_secs(60)),
+        Some((index, 'h')) => (&age[..index], Duration::from_secs(60 * 60)),
+        Some((index, 'd')) => (&age[..index], Duration::from_secs(24 * 60 * 60)),
+        _ => (age, Duration::from_secs(24 * 60 * 60))
+    };
+
+    match amount.parse::<u64>() {
+        //seconds are compared as amount * unit
+        Ok(amount) if amount.checked_mul(unit.as_secs()).is_none() => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Age '{}' is too large.", age))),
+        Ok(amount) => return Ok((ordering, amount, unit)),
+        Err(_) => return Err(Error::new(ErrorKind::InvalidInput, format!("Invoker Error: Invalid age '{}'.", age)))
+    }
+}
+
+
+fn split_ordering(value: &str) -> (cmp::Ordering, &str){
+    match value.chars().next() {
+        Some('+') => return (cmp::Ordering::Greater, &value[1..]),
+        Some('-') => return (cmp::Ordering::Less, &value[1..]),
+        _ => return (cmp::Ordering::Equal, value)
+    }
+}
+
+
+///Finds all entries under root that match the options. Links aren't followed and unreadable directories are skipped.
+pub fn find_entries(root: &Path, options: &FindOptions) -> Result<Vec<PathBuf>, Error>{
+    let mut results = Vec::<PathBuf>::new();
+    let now = SystemTime::now();
+
+    find_in(root, 1, options, now, &mut results)?;
+
+    return Ok(results)
+}
+
+
+fn find_in(directory: &Path, depth: usize, options: &FindOptions, now: SystemTime, results: &mut Vec<PathBuf>) -> Result<(), Error>{
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(directory)?.flatten().collect();
+    entries.sort_by_key(|x| x.file_name());
+
+    for entry in entries{
+        let metadata = match fs::symlink_metadata(entry.path()) {
+            Ok(metadata) => metadata,
+            Err(_) => continue
+        };
+
+        if !options.hidden && is_hidden(&entry.path(), &metadata, true){
+            continue;
+        }
+
+        if matches_entry(&entry, &metadata, options, now){
+            results.push(entry.path());
+        }
+
+        if metadata.is_dir() && options.max_depth.is_none_or(|x| depth < x){
+            let _ = find_in(&entry.path(), depth + 1, options, now, results);
+        }
+    }
+
+    return Ok(())
+}
+
+
+fn matches_entry(entry: &fs::DirEntry, metadata: &fs::Metadata, options: &FindOptions, now: SystemTime) -> bool{
+    if !options.name.is_match(&entry.file_name().to_string_lossy()){
+        return false
+    }
+
+    let entry_type = if metadata.file_type().is_symlink() {
+        EntryType::LINK
+    }
+    else if metadata.is_dir() {
+        EntryType::DIRECTORY
+    }
+    else {
+        EntryType::FILE
+    };
+    if options.entry_type.is_some_and(|x| x != entry_type){
+        return false
+    }
+
+    if let Some((ordering, amount, unit)) = options.size{
+        let size = metadata.len();
+        let matches = match ordering {
+            cmp::Ordering::Equal => size.div_ceil(unit) == amount,
+            _ => size.cmp(&(amount * unit)) == ordering
+        };
+        if !matches{
+            return false
+        }
+    }
+
+    if let Some((ordering, amount, unit)) = options.modified{
+        let age = match metadata.modified().ok().and_then(|x| now.duration_since(x).ok()) {
+            Some(age) => age,
+            None => Duration::ZERO
+        };
+        let matches = match ordering {
+            cmp::Ordering::Equal => (age.as_secs() / unit.as_secs()) == amount,
+            _ => age.cmp(&Duration::from_secs(amount * unit.as_secs())) == ordering
+        };
+        if !matches{
+            return false
+        }
+    }
+
+    return true
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String>{
+        return text.lines().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_build_regex(){
+        let options = SearchOptions { fixed: true, ..Default::default() };
+        assert!(build_regex("a.b(", &options).unwrap().is_match("xa.b(x"));
+        assert!(!build_regex("a.b(", &options).unwrap().is_match("axb("));
+
+        let options = SearchOptions { word: true, ignore_case: true, ..Default::default() };
+        assert!(build_regex("main", &options).unwrap().is_match("fn MAIN()"));
+        assert!(!build_regex("main", &options).unwrap().is_match("domain"));
+
+        assert!(build_regex("(", &SearchOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_search_lines(){
+        let text = lines("a\nmatch 1\nb\nc\nd\nmatch 2\nmatch 3\ne");
+        let regex = Regex::new("match").unwrap();
+
+        let options = SearchOptions { before: 1, after: 1, ..Default::default() };
+        let numbers: Vec<(usize, bool)> = search_lines(&text, &regex, &options).iter().map(|x| (x.line_number, x.is_match)).collect();
+        assert_eq!(numbers, vec![(1, false), (2, true), (3, false), (5, false), (6, true), (7, true), (8, false)]);
+
+        let options = SearchOptions { invert: true, ..Default::default() };
+        let numbers: Vec<usize> = search_lines(&text, &regex, &options).iter().map(|x| x.line_number).collect();
+        assert_eq!(numbers, vec![1, 3, 4, 5, 8]);
+    }
+
+    #[test]
+    fn test_ignore_rule(){
+        let (regex, negated, dir_only) = ignore_rule("target/").unwrap();
+        assert!(regex.is_match("target") && regex.is_match("sub/target"));
+        assert!(!negated && dir_only);
+
+        let (regex, _, _) = ignore_rule("/docs/*.md").unwrap();
+        assert!(regex.is_match("docs/readme.md"));
+        assert!(!regex.is_match("sub/docs/readme.md") && !regex.is_match("docs/a/readme.md"));
+
+        let (regex, _, _) = ignore_rule("logs/**/*.log").unwrap();
+        assert!(regex.is_match("logs/a.log") && regex.is_match("logs/x/y/a.log"));
+
+        let (_, negated, _) = ignore_rule("!keep.log").unwrap();
+        assert!(negated);
+
+        assert!(ignore_rule("# comment").is_none());
+        assert!(ignore_rule("").is_none());
+    }
+
+    #[test]
+    fn test_find_predicates(){
+        assert_eq!(parse_size("+10M").unwrap(), (cmp::Ordering::Greater, 10, 1024 * 1024));
+        assert_eq!(parse_size("-4k").unwrap(), (cmp::Ordering::Less, 4, 1024));
+        assert_eq!(parse_size("100").unwrap(), (cmp::Ordering::Equal, 100, 1));
+        assert!(parse_size("+M").is_err());
+        assert!(parse_size("+99999999999999G").is_err());
+
+        assert_eq!(parse_age("+7").unwrap(), (cmp::Ordering::Greater, 7, Duration::from_secs(24 * 60 * 60)));
+        assert_eq!(parse_age("-2h").unwrap(), (cmp::Ordering::Less, 2, Duration::from_secs(60 * 60)));
+        assert!(parse_age("soon").is_err());
+        assert!(parse_age("+500000000000000d").is_err());
+
+        let pattern = name_pattern("*.RS", false, true).unwrap();
+        assert!(pattern.is_match("main.rs") && !pattern.is_match("main.rs.bak"));
+        let pattern = name_pattern("ma(in|ke)", true, false).unwrap();
+        assert!(pattern.is_match("make") && !pattern.is_match("makefile"));
+    }
+}
@@ -0,0 +1,146 @@
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::copying::{CopyOptions, Overwrite};
+use super::functions;
+
+
+///Trashed item with what is needed to restore it. Deleted is in seconds since the epoch.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TrashEntry{
+    pub id: String,
+    pub original: PathBuf,
+    pub deleted: u64
+}
+
+
+///Per user trash directory. Trashed items are kept in files, their restore metadata in info as id.json.
+pub fn trash_directory() -> Result<PathBuf, Error>{
+    match dirs::data_local_dir() {
+        Some(directory) => return Ok(directory.join("rcli").join("trash")),
+        None => return Err(Error::new(ErrorKind::NotFound, "Invoker Error: Couldn't locate the user data directory."))
+    }
+}
+
+
+///Moves path into the trash and records where it came from.
+pub fn put(path: &Path) -> Result<TrashEntry, Error>{
+    if fs::symlink_metadata(path).is_err(){
+        return Err(Error::new(ErrorKind::NotFound, format!("Invoker Error: Path {} doesn't exist.", path.display())))
+    }
+
+    let trash = trash_directory()?;
+    fs::create_dir_all(trash.join("files"))?;
+    fs::create_dir_all(trash.join("info"))?;
+
+    //links are trashed themselves, so only their parent is resolved
+    let original = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => parent.canonicalize()?.join(name),
+        (_, Some(name)) => std::env::current_dir()?.join(name),
+        _ => path.canonicalize()?
+    };
+    if trash.starts_with(&original){
+        return Err(Error::new(ErrorKind::InvalidInput, "Invoker Error: Cannot trash a directory holding the trash."))
+    }
+
+    let deleted = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let name = original.file_name().map(|x| x.to_string_lossy().to_string()).unwrap_or_default();
+    let entry = TrashEntry { id: format!("{}.{}", name, deleted.as_nanos()), original, deleted: deleted.as_secs() };
+
+    //metadata is written first, an item without it couldn't be restored
+    let info = trash.join("info").join(format!("{}.json", entry.id));
+    fs::write(&info, serde_json::to_string_pretty(&entry)?)?;
+
+    let options = CopyOptions { overwrite: Overwrite::DENY, preserve: true, dereference: false, progress: false };
+    if let Err(error) = functions::r#move(&[path.to_path_buf()], &trash.join("files").join(&entry.id), &options){
+        let _ = fs::remove_file(&info);
+        return Err(error)
+    }
+
+    return Ok(entry)
+}
+
+
+///Trashed items, most recently deleted first.
+pub fn list() -> Result<Vec<TrashEntry>, Error>{
+    let info = trash_directory()?.join("info");
+    let mut entries = Vec::<TrashEntry>::new();
+
+    if !info.is_dir(){
+        return Ok(entries)
+    }
+
+    for file in fs::read_dir(info)?.flatten(){
+        //unreadable metadata is skipped rather than failing the whole listing
+        if let Some(entry) = fs::read_to_string(file.path()).ok().and_then(|x| serde_json::from_str::<TrashEntry>(&x).ok()){
+            entries.push(entry);
+        }
+    }
+
+    entries.sort_by(|a, b| b.deleted.cmp(&a.deleted).then(b.id.cmp(&a.id)));
+    return Ok(entries)
+}
+
+
+///Restores an item by id, original path or name. The most recently deleted match is restored, or the latest item without a name.
+pub fn restore(name: Option<&str>) -> Result<TrashEntry, Error>{
+    let entries = list()?;
+
+    let entry = match name {
+        Some(name) => entries.into_iter().find(|x| {
+            x.id == name || x.original == Path::new(name) || x.original.file_name().is_some_and(|y| y == name)
+        }),
+        None => entries.into_iter().next()
+    };
+    let entry = match entry {
+        Some(entry) => entry,
+        None => return Err(Error::new(ErrorKind::NotFound, "Invoker Error: No matching item in the trash."))
+    };
+
+    if fs::symlink_metadata(&entry.original).is_ok(){
+        return Err(Error::new(ErrorKind::AlreadyExists, format!("Invoker Error: {} exists, restoring would overwrite it.", entry.original.display())))
+    }
+    if let Some(parent) = entry.original.parent(){
+        fs::create_dir_all(parent)?;
+    }
+
+    let trash = trash_directory()?;
+    let options = CopyOptions { overwrite: Overwrite::DENY, preserve: true, dereference: false, progress: false };
+
+    functions::r#move(&[trash.join("files").join(&entry.id)], &entry.original, &options)?;
+    fs::remove_file(trash.join("info").join(format!("{}.json", entry.id)))?;
+
+    return Ok(entry)
+}
+
+
+///Permanently deletes everything in the trash, including items whose metadata is missing or unreadable.
+///Returns how many items were deleted.
+pub fn empty() -> Result<usize, Error>{
+    let trash = trash_directory()?;
+    let mut deleted: usize = 0;
+
+    for folder in ["files", "info"]{
+        let directory = trash.join(folder);
+        if !directory.is_dir(){
+            continue;
+        }
+
+        for item in fs::read_dir(&directory)?.flatten(){
+            match item.file_type() {
+                Ok(file_type) if file_type.is_dir() => fs::remove_dir_all(item.path())?,
+                _ => fs::remove_file(item.path())?
+            }
+
+            if folder == "files"{
+                deleted += 1;
+            }
+        }
+    }
+
+    return Ok(deleted)
+}
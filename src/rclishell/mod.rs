@@ -1 +1,2 @@
-pub mod shell;
\ No newline at end of file
+pub mod shell;
+pub mod script;
\ No newline at end of file
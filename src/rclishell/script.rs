@@ -0,0 +1,37 @@
+use std::fs;
+use std::io::Error;
+use std::path::Path;
+
+use crate::rcliparser::objects::grammar_objects::Grammar;
+use crate::rcliparser::objects::statement_objects::Statement;
+use crate::rcliparser::statement_parser::{self, StatementError};
+
+
+///Script source from a file, a -c one-liner or the prompt. Statements are created with the terminal grammar before execution.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Script{
+    name: String,
+    source: String
+}
+
+impl Script{
+    ///Reads a script file (.rcli).
+    pub fn from_file(path: &Path) -> Result<Self, Error>{
+        let source = fs::read_to_string(path)?;
+        return Ok(Script::from_string(path.display().to_string(), &source))
+    }
+
+    ///Creates a script from a string, for example a -c one-liner or a prompt line.
+    pub fn from_string(name: String, source: &str) -> Self{
+        return Script { name: name, source: source.to_string() }
+    }
+
+    pub fn get_name(&self) -> &String{
+        return &self.name
+    }
+
+    ///Parses the source to statements. Comments and empty lines are dropped.
+    pub fn get_statements(&self, grammar: &Grammar) -> Result<Vec<Statement>, StatementError>{
+        return statement_parser::parse_statements(&self.source, grammar)
+    }
+}
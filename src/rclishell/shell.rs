@@ -1,6 +1,8 @@
-use std::{process::ExitCode, sync::{Arc, Mutex}};
+use std::{io::ErrorKind, process::ExitCode, sync::{Arc, Mutex}};
 
-use crate::{rclilogger::logger::Logger, rcliparser::{objects::data_types::Data, parser}, rcliterminal::terminal::Terminal};
+use crate::{rclilogger::logger::Logger, rcliparser::evaluator::{Evaluator, Flow}, rcliterminal::terminal::Terminal};
+
+use super::script::Script;
 
 
 pub struct Shell{
@@ -15,10 +17,27 @@ impl Shell{
         }
     }
 
-    pub fn run(&self) -> ExitCode{
+    pub fn run(&self, load_rc: bool) -> ExitCode{
         //singlenton loop
         let mut terminal_instance = self.terminal.lock().unwrap();
         let logger = self.logger.lock().unwrap();
+
+        //execute startup file before the first prompt
+        if load_rc {
+            if let Some(rc_file) = terminal_instance.get_rc_file() {
+                match Script::from_file(&rc_file) {
+                    Ok(script) => {
+                        execute_script(&script, false, &mut terminal_instance, &logger);
+                    },
+                    Err(error) => {
+                        logger.log_err(format!("{}: {}", rc_file.display(), error));
+                    }
+                }
+            }
+        }
+
+        terminal_instance.set_interactive(true);
+
         'run: loop  {
             let mut input = String::new();
             let dir_display = terminal_instance.get_current_directory().display().to_string().replace(r"\\", r"\").replace(r"\?\", r"");
@@ -26,56 +45,97 @@ impl Shell{
             logger.log("============RCLI TERMINAL============\n");
             logger.lognn(String::from(format!("RCli {}>", dir_display)));
 
-            let user_input = std::io::stdin().read_line(&mut input);
+            'read: loop {
+                let user_input = std::io::stdin().read_line(&mut input);
 
-            //accept input
-            match user_input {
-                Ok(_) => {
-                    let operation_result = parser::parse(input, &mut *terminal_instance);
+                //accept input
+                match user_input {
+                    Ok(0) => {
+                        break 'run;
+                    },
+                    Ok(_) => {
+                        let script = Script::from_string(String::from("prompt"), &input);
+                        let statements = match script.get_statements(&terminal_instance.get_instance_grammar()) {
+                            Ok(statements) => statements,
+                            //unterminated block, read next line
+                            Err((_, error)) if error.kind() == ErrorKind::UnexpectedEof => {
+                                logger.lognn("> ");
+                                continue 'read;
+                            },
+                            Err((_, error)) => {
+                                logger.log_err(error);
+                                break 'read;
+                            }
+                        };
 
-                    match operation_result{
-                        Ok(data) => {
-                            match data {
-                                Data::PathData(path) => {
-                                    let path_to_string = path.display().to_string();
-                                    logger.format_log(path_to_string);
+                        let mut evaluator = Evaluator::new(&mut terminal_instance, false);
+                        let flow = evaluator.evaluate(&statements, &mut |_, operation_result| {
+                            match operation_result{
+                                Ok(data) => {
+                                    data.render_terminal().iter().for_each(|x| logger.log(x));
                                 },
-                                Data::StringData(data) => {
-                                    logger.log(data);
+                                Err(err) => {
+                                    logger.log_err(err);
                                 },
-                                Data::VecStringData(string_vec) => {
-                                    string_vec.iter().for_each(|x| logger.log(x));
-                                },
-                                Data::DirPathData(path_data) => {
-                                    path_data.iter().for_each(|x| {
-                                        let path_to_string = x.display().to_string();
-                                        logger.format_log(path_to_string);
-                                    })
-                                },
-                                Data::StatusData(status_code) => {
-                                    if status_code.eq(&1){
-                                        return ExitCode::SUCCESS;
-                                    }
-                                },
-                                Data::DataVector(boxed_data) => {
-                                    let data = *boxed_data;
-                                    
-                                    data.iter().for_each(|x| logger.format_log(x.get_value().unwrap().to_string()));
-                                }
-                                _ => unreachable!()
                             }
-                        },
-                        Err(err) => {
-                            logger.log_err(err);
-                        },
-                    }
-                },
-                Err(input_error) => {
-                    logger.log_err(input_error);
-                    break 'run;
-                },
+                        });
+
+                        let status = evaluator.get_status();
+                        terminal_instance.set_last_status(status);
+
+                        if flow == Flow::EXIT{
+                            return ExitCode::SUCCESS;
+                        }
+                        break 'read;
+                    },
+                    Err(input_error) => {
+                        logger.log_err(input_error);
+                        break 'run;
+                    },
+                }
             }
         }
         return ExitCode::FAILURE;
     }
-}
\ No newline at end of file
+
+    ///Runs a script non-interactively. Errors are reported with the script name and line number.
+    ///Exit code reflects the last executed command.
+    pub fn run_script(&self, script: &Script, stop_on_error: bool) -> ExitCode{
+        let mut terminal_instance = self.terminal.lock().unwrap();
+        let logger = self.logger.lock().unwrap();
+
+        return execute_script(script, stop_on_error, &mut terminal_instance, &logger)
+    }
+}
+
+
+///Executes script statements. Shared by scripts, one-liners and the startup file.
+fn execute_script(script: &Script, stop_on_error: bool, terminal_instance: &mut Terminal, logger: &Logger) -> ExitCode{
+    let statements = match script.get_statements(&terminal_instance.get_instance_grammar()) {
+        Ok(statements) => statements,
+        Err((line, error)) => {
+            logger.log_err(format!("{}:{}: {}", script.get_name(), line, error));
+            return ExitCode::FAILURE
+        }
+    };
+
+    let mut evaluator = Evaluator::new(terminal_instance, stop_on_error);
+    let flow = evaluator.evaluate(&statements, &mut |line, operation_result| {
+        match operation_result{
+            Ok(data) => {
+                data.render_terminal().iter().for_each(|x| logger.log(x));
+            },
+            Err(err) => {
+                logger.log_err(format!("{}:{}: {}", script.get_name(), line, err));
+            }
+        }
+    });
+
+    let status = evaluator.get_status();
+    terminal_instance.set_last_status(status);
+
+    if flow == Flow::EXIT{
+        return ExitCode::SUCCESS
+    }
+    return ExitCode::from(status)
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::env;
 use std::io::Error;
 use std::path::PathBuf;
@@ -5,22 +6,45 @@ use std::sync::Mutex;
 
 use crate::rcliparser::objects::data_types::Data;
 use crate::rcliparser::objects::grammar_objects::Grammar;
+use crate::rcliparser::objects::statement_objects::Statement;
 use crate::rcliparser::utils::grammar_reader;
 
+const RC_FILE: &str = ".rclirc";
+///Variable holding the previous directory, used by cd -.
+const OLDPWD: &str = "OLDPWD";
+
 
 ///Singlenton terminal
 pub struct Terminal{
     user_home_directory: Mutex<PathBuf>,
     current_directory: Mutex<PathBuf>,
-    grammar: Mutex<Grammar>
+    directory_stack: Mutex<Vec<PathBuf>>,
+    interactive: Mutex<bool>,
+    last_status: Mutex<u8>,
+    grammar: Mutex<Grammar>,
+    rc_file: Mutex<Option<PathBuf>>,
+    variables: Mutex<HashMap<String, String>>,
+    functions: Mutex<HashMap<String, Vec<Statement>>>
 }
 
 impl Terminal{
     pub fn new() -> Self{
+        let home_directory = dirs::home_dir().unwrap();
+
+        //startup file is optional
+        let rc_path = home_directory.join(RC_FILE);
+        let rc_file = if rc_path.is_file() { Some(rc_path) } else { None };
+
         Terminal {
-            user_home_directory: Mutex::new(dirs::home_dir().unwrap()), 
+            user_home_directory: Mutex::new(home_directory), 
             current_directory: Mutex::new(env::current_dir().unwrap()), 
-            grammar: Mutex::new(grammar_reader::load_grammar()) 
+            directory_stack: Mutex::new(Vec::new()),
+            interactive: Mutex::new(false),
+            last_status: Mutex::new(0),
+            grammar: Mutex::new(grammar_reader::load_grammar()),
+            rc_file: Mutex::new(rc_file),
+            variables: Mutex::new(HashMap::new()),
+            functions: Mutex::new(HashMap::new())
         }
     }
 
@@ -34,7 +58,8 @@ impl Terminal{
                 //todo! handle error in case the operation fails
                 match operation_results {
                     Ok(_) => {
-                        *current_dir = env::current_dir().unwrap();
+                        let previous = std::mem::replace(&mut *current_dir, env::current_dir().unwrap());
+                        self.variables.lock().unwrap().insert(OLDPWD.to_string(), previous.display().to_string());
                         return Ok(Data::StatusData(100));
                     },
                     Err(error) => {
@@ -60,4 +85,62 @@ impl Terminal{
     pub fn get_current_directory(&self) -> PathBuf{
         return self.current_directory.lock().unwrap().to_path_buf()
     }
+
+    ///Directory before the last change, if it was changed.
+    pub fn get_previous_directory(&self) -> Option<PathBuf>{
+        return self.get_variable(OLDPWD).map(PathBuf::from)
+    }
+
+    ///Saved directories, the most recently pushed last.
+    pub fn get_directory_stack(&self) -> Vec<PathBuf>{
+        return self.directory_stack.lock().unwrap().clone()
+    }
+
+    pub fn push_directory(&mut self, path: PathBuf){
+        self.directory_stack.lock().unwrap().push(path);
+    }
+
+    pub fn pop_directory(&mut self) -> Option<PathBuf>{
+        return self.directory_stack.lock().unwrap().pop()
+    }
+
+    ///If commands are typed at the prompt. Scripts, one-liners and the startup file aren't interactive.
+    pub fn is_interactive(&self) -> bool{
+        return *self.interactive.lock().unwrap()
+    }
+
+    pub fn set_interactive(&mut self, interactive: bool){
+        *self.interactive.lock().unwrap() = interactive;
+    }
+
+    ///Status of the last command, kept between prompts for $?.
+    pub fn get_last_status(&self) -> u8{
+        return *self.last_status.lock().unwrap()
+    }
+
+    pub fn set_last_status(&mut self, status: u8){
+        *self.last_status.lock().unwrap() = status;
+    }
+
+    ///Startup file (~/.rclirc) if it exists.
+    pub fn get_rc_file(&self) -> Option<PathBuf>{
+        return self.rc_file.lock().unwrap().clone()
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<String>{
+        return self.variables.lock().unwrap().get(name).cloned()
+    }
+
+    pub fn set_variable(&mut self, name: String, value: String){
+        self.variables.lock().unwrap().insert(name, value);
+    }
+
+    ///User defined function body.
+    pub fn get_function(&self, name: &str) -> Option<Vec<Statement>>{
+        return self.functions.lock().unwrap().get(name).cloned()
+    }
+
+    pub fn set_function(&mut self, name: String, body: Vec<Statement>){
+        self.functions.lock().unwrap().insert(name, body);
+    }
 }
\ No newline at end of file